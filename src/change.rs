@@ -0,0 +1,183 @@
+//! Tracking of the edits that have not been written back yet.
+
+use crate::{DependencyKind, Metadata};
+
+/// An edit to a manifest that has not been written back by `dump` yet.
+///
+/// Repeated edits of the same value are merged into a single change from the value
+/// at the last `dump` to the current one, and a change that restores the original
+/// value disappears.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The version of a package changed.
+    ///
+    /// A version inherited from `[workspace.package]` is reported for the package it
+    /// was edited through.
+    Version {
+        package: String,
+        from: semver::Version,
+        to: semver::Version,
+    },
+    /// A key in `[package]` was set or removed. Values are written in TOML syntax, except
+    /// that strings are unquoted; `None` stands for a missing key.
+    PackageField {
+        package: String,
+        key: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The requirement on a dependency changed, or the dependency was added or removed.
+    /// `None` stands for a missing dependency, and `*` for a dependency without a version.
+    ///
+    /// `target` is the platform of a dependency declared under `[target]`, e.g. `cfg(windows)`.
+    Dependency {
+        package: String,
+        target: Option<String>,
+        kind: DependencyKind,
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The requirement on a dependency in `[workspace.dependencies]` changed.
+    WorkspaceDependency {
+        name: String,
+        from: String,
+        to: String,
+    },
+    /// Another part of a manifest was edited, e.g. a feature, a badge or the layout of a
+    /// dependency table. `key` is the dotted path of what changed, e.g. `features.std`,
+    /// `profile.release.lto` or `workspace.members`.
+    ///
+    /// `package` is `None` for the tables of the root manifest that apply to the whole
+    /// workspace, i.e. `[workspace]` and `[profile]`.
+    Other {
+        package: Option<String>,
+        key: String,
+    },
+}
+
+impl Change {
+    /// Return `true` if `self` and `other` change the same value.
+    fn same_target(&self, other: &Change) -> bool {
+        match (self, other) {
+            (Change::Version { package: a, .. }, Change::Version { package: b, .. }) => a == b,
+            (
+                Change::PackageField {
+                    package: a,
+                    key: a_key,
+                    ..
+                },
+                Change::PackageField {
+                    package: b,
+                    key: b_key,
+                    ..
+                },
+            ) => a == b && a_key == b_key,
+            (
+                Change::Dependency {
+                    package: a,
+                    target: a_target,
+                    kind: a_kind,
+                    name: a_name,
+                    ..
+                },
+                Change::Dependency {
+                    package: b,
+                    target: b_target,
+                    kind: b_kind,
+                    name: b_name,
+                    ..
+                },
+            ) => a == b && a_target == b_target && a_kind == b_kind && a_name == b_name,
+            (
+                Change::WorkspaceDependency { name: a, .. },
+                Change::WorkspaceDependency { name: b, .. },
+            ) => a == b,
+            (
+                Change::Other {
+                    package: a,
+                    key: a_key,
+                },
+                Change::Other {
+                    package: b,
+                    key: b_key,
+                },
+            ) => a == b && a_key == b_key,
+            _ => false,
+        }
+    }
+
+    /// Take the new value of `later`, a change of the same value made after `self`.
+    fn merge(&mut self, later: Change) {
+        match (self, later) {
+            (Change::Version { to, .. }, Change::Version { to: later, .. }) => *to = later,
+            (Change::PackageField { to, .. }, Change::PackageField { to: later, .. }) => {
+                *to = later
+            }
+            (Change::Dependency { to, .. }, Change::Dependency { to: later, .. }) => *to = later,
+            (
+                Change::WorkspaceDependency { to, .. },
+                Change::WorkspaceDependency { to: later, .. },
+            ) => *to = later,
+            _ => {}
+        }
+    }
+
+    /// Return `true` if the change leaves the value as it was.
+    fn is_noop(&self) -> bool {
+        match self {
+            Change::Version { from, to, .. } => from == to,
+            Change::PackageField { from, to, .. } => from == to,
+            Change::Dependency { from, to, .. } => from == to,
+            Change::WorkspaceDependency { from, to, .. } => from == to,
+            Change::Other { .. } => false,
+        }
+    }
+}
+
+impl Metadata {
+    /// Return the edits made since the manifests were loaded or last written by `dump`,
+    /// in the order they were first made.
+    ///
+    /// Versions, keys in `[package]` and dependency requirements are reported with their
+    /// old and new values; the other edits, e.g. of `[features]` or of the layout of a
+    /// dependency table, are reported as `Change::Other`.
+    pub fn pending_changes(&self) -> Vec<Change> {
+        self.changes.clone()
+    }
+
+    /// Remember `change`, merging it with an earlier change of the same value.
+    pub(crate) fn record_change(&mut self, change: Change) {
+        match self.changes.iter().position(|c| c.same_target(&change)) {
+            Some(i) => {
+                self.changes[i].merge(change);
+                if self.changes[i].is_noop() {
+                    self.changes.remove(i);
+                }
+            }
+            None if !change.is_noop() => self.changes.push(change),
+            None => {}
+        }
+    }
+
+    /// Remember that `key` of `package` was edited, see `Change::Other`.
+    pub(crate) fn record_edit(&mut self, package: &str, key: String) {
+        self.record_change(Change::Other {
+            package: Some(package.to_owned()),
+            key,
+        });
+    }
+
+    /// Remember that `key` of the root manifest was edited, see `Change::Other`.
+    pub(crate) fn record_root_edit(&mut self, key: String) {
+        self.record_change(Change::Other { package: None, key });
+    }
+}
+
+/// Return the value of `item` as reported in a `Change`.
+pub(crate) fn display_item(item: &toml_edit::Item) -> String {
+    match item.as_str() {
+        Some(s) => s.to_owned(),
+        None => item.to_string().trim().to_owned(),
+    }
+}
@@ -0,0 +1,75 @@
+//! Reading of the Cargo configuration that applies to the workspace.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Metadata;
+
+impl Metadata {
+    /// Return the names of the registries declared in `[registries]` of the Cargo
+    /// configuration that applies to the workspace, sorted and without duplicates.
+    ///
+    /// Like Cargo, `.cargo/config.toml` (or `.cargo/config`) is read in the workspace root,
+    /// in each of its ancestors and in `$CARGO_HOME`, and registries declared with
+    /// `CARGO_REGISTRIES_<NAME>_INDEX` environment variables are included. `crates-io`
+    /// is always available and not listed.
+    pub fn known_registries(&self) -> Vec<String> {
+        let mut registries = Vec::new();
+        for path in config_files(&self.metadata.workspace_root) {
+            let config = match fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| content.parse::<toml_edit::DocumentMut>().ok())
+            {
+                Some(config) => config,
+                None => continue,
+            };
+            if let Some(table) = config.get("registries").and_then(|r| r.as_table_like()) {
+                registries.extend(table.iter().map(|(name, _)| name.to_owned()));
+            }
+        }
+        for (key, _) in env::vars() {
+            if let Some(name) = key
+                .strip_prefix("CARGO_REGISTRIES_")
+                .and_then(|key| key.strip_suffix("_INDEX"))
+            {
+                registries.push(name.to_lowercase().replace('_', "-"));
+            }
+        }
+        registries.sort();
+        registries.dedup();
+        registries
+    }
+}
+
+/// Return the paths of the configuration files Cargo reads when run in `dir`, starting
+/// with the most specific one.
+fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = dir
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .collect::<Vec<_>>();
+    let cargo_home = env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")));
+    if let Some(cargo_home) = cargo_home {
+        if !dirs.contains(&cargo_home) {
+            dirs.push(cargo_home);
+        }
+    }
+
+    dirs.into_iter()
+        .filter_map(|dir| {
+            let config = dir.join("config.toml");
+            if config.is_file() {
+                return Some(config);
+            }
+            let config = dir.join("config");
+            if config.is_file() {
+                Some(config)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+//! Pick the next version from commit messages written in the
+//! [Conventional Commits](https://www.conventionalcommits.org) style.
+//!
+//! Nothing here reads git; pass the messages, e.g. from `git log --format=%B`.
+
+use crate::BumpLevel;
+
+/// Return the part of the version to bump for the given commit messages:
+///
+/// * `Major` if any commit is marked as breaking, by a `!` before the colon of the subject,
+///   e.g. `feat!: ...`, or by a line starting with `BREAKING CHANGE:`, be it a footer or
+///   the subject,
+/// * `Minor` if any commit subject is of type `feat`, e.g. `feat(parser): ...`,
+/// * `Patch` otherwise, including for `fix` commits and messages that do not follow the
+///   convention.
+///
+/// Pass the result to `Metadata::bump_version`, or use `Metadata::bump_breaking` for
+/// `Major` to keep the version below `1.0.0`.
+pub fn suggest_bump(commits: &[&str]) -> BumpLevel {
+    let mut level = BumpLevel::Patch;
+    for commit in commits {
+        let subject = commit.lines().next().unwrap_or_default();
+        let commit_type = match subject.find(':') {
+            Some(colon) => subject[..colon].trim(),
+            None => "",
+        };
+        // The subject counts too, as callers may pass subject lines only.
+        let breaking = commit_type.ends_with('!')
+            || commit.lines().any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            });
+        if breaking {
+            return BumpLevel::Major;
+        }
+        let commit_type = match commit_type.find('(') {
+            Some(paren) => &commit_type[..paren],
+            None => commit_type,
+        };
+        if commit_type.eq_ignore_ascii_case("feat") {
+            level = BumpLevel::Minor;
+        }
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaking_changes_bump_major() {
+        assert_eq!(suggest_bump(&["feat!: drop x"]), BumpLevel::Major);
+        assert_eq!(suggest_bump(&["fix(parser)!: drop x"]), BumpLevel::Major);
+        assert_eq!(
+            suggest_bump(&["fix: y\n\nBREAKING CHANGE: drop x"]),
+            BumpLevel::Major
+        );
+        assert_eq!(suggest_bump(&["BREAKING CHANGE: drop x"]), BumpLevel::Major);
+    }
+
+    #[test]
+    fn features_bump_minor() {
+        assert_eq!(
+            suggest_bump(&["fix: y", "feat(parser): x"]),
+            BumpLevel::Minor
+        );
+        assert_eq!(suggest_bump(&["fix: y", "chore: z"]), BumpLevel::Patch);
+        assert_eq!(suggest_bump(&["not conventional"]), BumpLevel::Patch);
+    }
+}
@@ -0,0 +1,102 @@
+//! `[package]` keys whose values crates.io restricts.
+
+use crate::{ManifestError, Metadata};
+
+/// The maximum number of keywords and of categories crates.io accepts.
+const MAX_ENTRIES: usize = 5;
+/// The maximum length of a keyword crates.io accepts.
+const MAX_KEYWORD_LEN: usize = 20;
+
+impl Metadata {
+    /// Overwrite `keywords` in `[package]` of `package`.
+    /// An empty slice removes the key.
+    ///
+    /// Return an error if the package does not exist or crates.io would reject the keywords,
+    /// see `check_keywords`.
+    pub fn set_keywords(
+        &mut self,
+        package: &str,
+        keywords: &[String],
+    ) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        Self::check_keywords(keywords)?;
+        self.set_package_array(package, "keywords", keywords)
+    }
+
+    /// Overwrite `categories` in `[package]` of `package`.
+    /// An empty slice removes the key.
+    ///
+    /// Return an error if the package does not exist or crates.io would reject the
+    /// categories, see `check_categories`.
+    pub fn set_categories(
+        &mut self,
+        package: &str,
+        categories: &[String],
+    ) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        Self::check_categories(categories)?;
+        self.set_package_array(package, "categories", categories)
+    }
+
+    /// Check `keywords` against the limits of crates.io without writing anything: at most
+    /// five keywords of at most 20 characters, each starting with an ASCII letter followed by
+    /// ASCII letters, digits, `_`, `-` or `+`.
+    pub fn check_keywords(keywords: &[String]) -> Result<(), ManifestError> {
+        check_len("keywords", keywords)?;
+        for keyword in keywords {
+            let reason = if keyword.len() > MAX_KEYWORD_LEN {
+                format!("must be at most {} characters long", MAX_KEYWORD_LEN)
+            } else if !keyword.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                "must start with an ASCII letter".to_owned()
+            } else if !keyword
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+')
+            {
+                "may only contain ASCII letters, digits, `_`, `-` and `+`".to_owned()
+            } else {
+                continue;
+            };
+            return Err(ManifestError::InvalidValue {
+                key: "keywords".to_owned(),
+                value: keyword.clone(),
+                reason,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check `categories` against the limits of crates.io without writing anything: at most
+    /// five categories. Whether a category is one of the slugs crates.io knows is not
+    /// checked, as the list changes over time.
+    pub fn check_categories(categories: &[String]) -> Result<(), ManifestError> {
+        check_len("categories", categories)
+    }
+
+    fn set_package_array(
+        &mut self,
+        package: &str,
+        key: &str,
+        values: &[String],
+    ) -> Result<(), ManifestError> {
+        let written = if values.is_empty() {
+            self.remove_package_key(package, key)
+        } else {
+            let mut array = values.iter().collect::<toml_edit::Array>();
+            self.style.format_array(&mut array);
+            self.set_package_value(package, key, array.into())
+                .map(|_| ())
+        };
+        written.ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))
+    }
+}
+
+fn check_len(key: &str, values: &[String]) -> Result<(), ManifestError> {
+    if values.len() > MAX_ENTRIES {
+        return Err(ManifestError::InvalidValue {
+            key: key.to_owned(),
+            value: values.join(", "),
+            reason: format!("crates.io accepts at most {} entries", MAX_ENTRIES),
+        });
+    }
+    Ok(())
+}
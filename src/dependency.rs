@@ -0,0 +1,665 @@
+//! Helpers to walk the dependency tables of a manifest.
+
+use crate::ManifestError;
+
+/// All kinds of dependencies.
+pub(crate) const DEPENDENCY_KINDS: [DependencyKind; 3] = [
+    DependencyKind::Normal,
+    DependencyKind::Development,
+    DependencyKind::Build,
+];
+
+/// Return the path, e.g. `target.'cfg(windows)'.dependencies`, and the kind of every
+/// dependency table declared in `doc`, including the platform-specific ones under `[target]`.
+pub(crate) fn dependency_tables(
+    doc: &toml_edit::DocumentMut,
+) -> Vec<(String, DependencyKind, &toml_edit::Item)> {
+    let mut tables = Vec::new();
+    for (key, item) in doc.iter() {
+        if let Some(kind) = DependencyKind::from_table_name(key) {
+            tables.push((key.to_owned(), kind, item));
+        }
+    }
+    let targets = doc
+        .get("target")
+        .and_then(|targets| targets.as_table_like());
+    for (cfg, target) in targets.into_iter().flat_map(|targets| targets.iter()) {
+        let target = target.as_table_like();
+        for (key, item) in target.into_iter().flat_map(|target| target.iter()) {
+            if let Some(kind) = DependencyKind::from_table_name(key) {
+                tables.push((format!("target.'{}'.{}", cfg, key), kind, item));
+            }
+        }
+    }
+    tables
+}
+
+/// Return the platform, e.g. `cfg(windows)`, the kind and the item of every dependency table
+/// declared in `doc`, including the platform-specific ones under `[target]`.
+pub(crate) fn dependency_tables_mut(
+    doc: &mut toml_edit::DocumentMut,
+) -> Vec<(Option<String>, DependencyKind, &mut toml_edit::Item)> {
+    let mut tables = Vec::new();
+    for (key, item) in doc.iter_mut() {
+        if key.get() == "target" {
+            let targets = item.as_table_like_mut();
+            for (cfg, target) in targets.into_iter().flat_map(|targets| targets.iter_mut()) {
+                let cfg = cfg.get().to_owned();
+                let target = target.as_table_like_mut();
+                for (key, item) in target.into_iter().flat_map(|target| target.iter_mut()) {
+                    if let Some(kind) = DependencyKind::from_table_name(key.get()) {
+                        tables.push((Some(cfg.clone()), kind, item));
+                    }
+                }
+            }
+        } else if let Some(kind) = DependencyKind::from_table_name(key.get()) {
+            tables.push((None, kind, item));
+        }
+    }
+    tables
+}
+
+/// Return the dotted path of the dependency table of the given kind, e.g.
+/// `target.'cfg(windows)'.dependencies` for `cfg(windows)`.
+pub(crate) fn table_path(target: Option<&str>, kind: DependencyKind) -> String {
+    match target {
+        Some(target) => format!("target.'{}'.{}", target, kind.table_name()),
+        None => kind.table_name().to_owned(),
+    }
+}
+
+/// Call `f` with the platform, the kind, the key and the entry of every dependency
+/// declared in `doc`.
+pub(crate) fn for_each_dependency_mut<F>(doc: &mut toml_edit::DocumentMut, mut f: F)
+where
+    F: FnMut(Option<&str>, DependencyKind, &str, &mut toml_edit::Item),
+{
+    for (target, kind, deps) in dependency_tables_mut(doc) {
+        if let Some(deps) = deps.as_table_like_mut() {
+            for (key, dep) in deps.iter_mut() {
+                f(target.as_deref(), kind, key.get(), dep);
+            }
+        }
+    }
+}
+
+/// Make the local dependencies on the crate `old` in `deps` that are renamed with the
+/// `package` field, e.g. `alias = { package = "old", ... }`, refer to `new` instead.
+/// Return `true` if anything changed.
+///
+/// Only dependencies via `path` or inherited from the workspace are rewritten.
+pub(crate) fn rename_package_fields(
+    deps: &mut dyn toml_edit::TableLike,
+    old: &str,
+    new: &str,
+) -> bool {
+    let mut changed = false;
+    for (_, dep) in deps.iter_mut() {
+        if !is_local(dep) {
+            continue;
+        }
+        if let Some(package) = dep
+            .as_table_like_mut()
+            .and_then(|dep| dep.get_mut("package"))
+        {
+            if package.as_str() == Some(old) {
+                crate::set_string(package, new.to_owned());
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Rename the local dependency on the crate `old` declared under its own name in `deps`
+/// to `new`. Return `true` if the key was renamed.
+///
+/// Only dependencies via `path` or inherited from the workspace are renamed.
+pub(crate) fn rename_dependency_key(
+    deps: &mut dyn toml_edit::TableLike,
+    old: &str,
+    new: &str,
+) -> bool {
+    let renames_key = deps
+        .get(old)
+        .is_some_and(|dep| is_local(dep) && dep.get("package").is_none());
+    if renames_key {
+        rename_key(deps, old, new);
+    }
+    renames_key
+}
+
+fn is_local(dep: &toml_edit::Item) -> bool {
+    is_path_dependency(dep) || crate::is_inherited(dep)
+}
+
+/// Rename the key `old` of `table` to `new`, keeping the order and formatting of the entries.
+fn rename_key(table: &mut dyn toml_edit::TableLike, old: &str, new: &str) {
+    let keys = table
+        .iter()
+        .map(|(key, _)| key.to_owned())
+        .collect::<Vec<_>>();
+    for key in &keys {
+        let mut formatted_key = match table.key(key) {
+            Some(formatted_key) => formatted_key.clone(),
+            None => continue,
+        };
+        let item = match table.remove(key) {
+            Some(item) => item,
+            None => continue,
+        };
+        if key == old {
+            formatted_key =
+                toml_edit::Key::new(new).with_leaf_decor(formatted_key.leaf_decor().clone());
+        }
+        table.entry_format(&formatted_key).or_insert(item);
+    }
+}
+
+/// Return the name of the crate the dependency entry refers to,
+/// honoring the `package` field of renamed dependencies.
+pub(crate) fn crate_name<'a>(key: &'a str, dep: &'a toml_edit::Item) -> &'a str {
+    dep.get("package")
+        .and_then(|package| package.as_str())
+        .unwrap_or(key)
+}
+
+/// Return `true` if the dependency entry points to a local crate via `path`.
+pub(crate) fn is_path_dependency(dep: &toml_edit::Item) -> bool {
+    dep.get("path").is_some()
+}
+
+/// Return the version requirement written in the dependency entry, if any.
+pub(crate) fn requirement(dep: &toml_edit::Item) -> Option<&str> {
+    match dep.as_str() {
+        Some(req) => Some(req),
+        None => dep.get("version")?.as_str(),
+    }
+}
+
+/// Return the version requirement of the dependency entry as reported in a `Change`,
+/// i.e. `*` if the entry has no version.
+pub(crate) fn display_requirement(dep: &toml_edit::Item) -> String {
+    requirement(dep).unwrap_or("*").to_owned()
+}
+
+/// Rewrite the requirement `req` so that it accepts `new_version`, keeping its operator
+/// and precision, e.g. `~0.3` becomes `~0.4` and `=0.3.0` becomes `=0.4.0` for `0.4.0`.
+///
+/// Requirements with more than one comparator, e.g. `>=0.3, <0.4`, are replaced by
+/// `new_version` itself.
+pub(crate) fn bump_requirement(req: &str, new_version: &semver::Version) -> String {
+    let fallback = new_version.to_string();
+    let req = req.trim();
+    if req.contains(',') {
+        return fallback;
+    }
+    let (op, version) = match req.find(|c: char| c.is_ascii_digit()) {
+        Some(i) => req.split_at(i),
+        None => return fallback,
+    };
+    let op = op.trim();
+    if !["", "=", "^", "~"].contains(&op) {
+        return fallback;
+    }
+
+    let parts = version.split('.').collect::<Vec<_>>();
+    let new_parts = [new_version.major, new_version.minor, new_version.patch];
+    let bumped = if new_version.is_prerelease() || parts.len() > 3 {
+        format!("{}{}", op, new_version)
+    } else {
+        // Keep wildcards such as `0.3.*` and the number of components.
+        let version = parts
+            .iter()
+            .zip(new_parts.iter())
+            .map(|(part, new_part)| match *part {
+                "*" | "x" | "X" => part.to_string(),
+                _ => new_part.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        format!("{}{}", op, version)
+    };
+    match semver::VersionReq::parse(&bumped) {
+        Ok(bumped_req) if bumped_req.matches(new_version) => bumped,
+        _ => fallback,
+    }
+}
+
+/// Rewrite the requirement of the dependency entry `dep` on the workspace member `name`,
+/// declared under `key`, if it is a `path` dependency whose requirement matches
+/// `old_version` but not `new_version`. See `bump_requirement`.
+///
+/// Return the old and the new requirement if the entry was changed.
+pub(crate) fn update_path_requirement(
+    key: &str,
+    dep: &mut toml_edit::Item,
+    name: &str,
+    old_version: &semver::Version,
+    new_version: &semver::Version,
+) -> Option<(String, String)> {
+    if crate_name(key, dep) != name || !is_path_dependency(dep) {
+        return None;
+    }
+    let raw_req = dep.as_table_like_mut()?.get_mut("version")?;
+    let old_req = raw_req.as_str()?.to_owned();
+    let req = semver::VersionReq::parse(&old_req).ok()?;
+    if !req.matches(old_version) || req.matches(new_version) {
+        return None;
+    }
+    let new_req = bump_requirement(&old_req, new_version);
+    crate::set_string(raw_req, new_req.clone());
+    Some((old_req, new_req))
+}
+
+/// Set the version requirement of the dependency entry to `req`.
+/// Return `true` if the entry was changed.
+pub(crate) fn set_requirement(dep: &mut toml_edit::Item, req: &str) -> bool {
+    if dep.is_str() {
+        if dep.as_str() == Some(req) {
+            return false;
+        }
+        crate::set_string(dep, req.to_owned());
+        return true;
+    }
+
+    if let Some(table) = dep.as_inline_table_mut() {
+        if !table.contains_key("version") {
+            push_inline(table, "version", req.into());
+            return true;
+        }
+    }
+    match dep.as_table_like_mut() {
+        Some(table) => match table.get_mut("version") {
+            Some(version) if version.as_str() == Some(req) => false,
+            Some(version) => {
+                crate::set_string(version, req.to_owned());
+                true
+            }
+            None => {
+                table.insert("version", toml_edit::value(req));
+                true
+            }
+        },
+        None => false,
+    }
+}
+
+/// Remove `path` from the dependency entry if it also has a version, so that the registry
+/// version is used. An inline table left with only the version is written in the short
+/// form `dep = "1.0"`. Return `true` if the entry was changed.
+pub(crate) fn strip_path(dep: &mut toml_edit::Item) -> bool {
+    let table = match dep.as_table_like_mut() {
+        Some(table) if table.contains_key("version") => table,
+        _ => return false,
+    };
+    if table.remove("path").is_none() {
+        return false;
+    }
+    if let Some(inline) = dep.as_inline_table() {
+        if inline.len() == 1 {
+            if let Some(version) = inline.get("version") {
+                let mut version = version.clone();
+                *version.decor_mut() = inline.decor().clone();
+                *dep = toml_edit::Item::Value(version);
+            }
+        }
+    }
+    true
+}
+
+/// Insert `key = value` at the end of `table`, moving the space before the closing brace
+/// from the previously last value to the new one.
+pub(crate) fn push_inline(table: &mut toml_edit::InlineTable, key: &str, value: toml_edit::Value) {
+    let suffix = table.iter_mut().last().and_then(|(_, last)| {
+        let suffix = last.decor().suffix().cloned();
+        last.decor_mut().set_suffix("");
+        suffix
+    });
+    table.insert(key, value);
+    if let (Some(suffix), Some(new)) = (suffix, table.get_mut(key)) {
+        new.decor_mut().set_suffix(suffix);
+    }
+}
+
+/// Remove `key` from `table` like `push_inline` in reverse, moving the space before the
+/// closing brace to the new last value.
+pub(crate) fn remove_inline(table: &mut toml_edit::InlineTable, key: &str) -> bool {
+    let is_last = table.iter().last().is_some_and(|(last, _)| last == key);
+    let removed = match table.remove(key) {
+        Some(removed) => removed,
+        None => return false,
+    };
+    if is_last {
+        if let (Some(suffix), Some((_, last))) = (removed.decor().suffix(), table.iter_mut().last())
+        {
+            last.decor_mut().set_suffix(suffix.clone());
+        }
+    }
+    true
+}
+
+/// The kind of a dependency, i.e. the table it is declared in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    /// `[dependencies]`
+    Normal,
+    /// `[dev-dependencies]`
+    Development,
+    /// `[build-dependencies]`
+    Build,
+}
+
+impl DependencyKind {
+    fn from_table_name(table_name: &str) -> Option<Self> {
+        DEPENDENCY_KINDS
+            .iter()
+            .copied()
+            .find(|kind| kind.table_name() == table_name)
+    }
+
+    /// Return the name of the table that declares dependencies of this kind.
+    pub fn table_name(self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "dependencies",
+            DependencyKind::Development => "dev-dependencies",
+            DependencyKind::Build => "build-dependencies",
+        }
+    }
+}
+
+/// How a dependency with more than a version requirement is written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DepStyle {
+    /// `foo = { version = "1.0", features = ["x"] }`
+    Inline,
+    /// A table of its own:
+    ///
+    /// ```toml
+    /// [dependencies.foo]
+    /// version = "1.0"
+    /// features = ["x"]
+    /// ```
+    Detailed,
+}
+
+/// Rewrite every table-like entry of `deps` in the given style.
+/// Entries in the short form `foo = "1.0"` are left as is.
+/// Return `true` if any entry was rewritten.
+pub(crate) fn set_style(deps: &mut toml_edit::Table, style: DepStyle) -> bool {
+    let mut changed = false;
+    for (mut key, dep) in deps.iter_mut() {
+        let new_dep = match (style, std::mem::take(dep)) {
+            (DepStyle::Inline, toml_edit::Item::Table(table)) => {
+                // Move the comments above the header and after it to the line of the entry.
+                let prefix = decor_str(table.decor().prefix());
+                let suffix = decor_str(table.decor().suffix());
+                let mut value = toml_edit::Value::InlineTable(table.into_inline_table());
+                if suffix.trim_start().starts_with('#') {
+                    value.decor_mut().set_suffix(suffix);
+                }
+                key.leaf_decor_mut().clear();
+                if !prefix.trim().is_empty() {
+                    key.leaf_decor_mut().set_prefix(prefix);
+                }
+                toml_edit::Item::Value(value)
+            }
+            (DepStyle::Detailed, toml_edit::Item::Value(toml_edit::Value::InlineTable(table))) => {
+                // Move the comments above the entry and after it around the header.
+                let mut prefix = decor_str(key.leaf_decor().prefix());
+                let suffix = decor_str(table.decor().suffix());
+                let mut table = table.into_table();
+                if !prefix.trim().is_empty() {
+                    // Keep a blank line before the header.
+                    if !prefix.starts_with('\n') && !prefix.starts_with("\r\n") {
+                        prefix.insert(0, '\n');
+                    }
+                    table.decor_mut().set_prefix(prefix);
+                }
+                if suffix.trim_start().starts_with('#') {
+                    table.decor_mut().set_suffix(suffix);
+                }
+                // The spaces around `=` of `foo = { ... }` do not belong in a table header.
+                key.leaf_decor_mut().clear();
+                toml_edit::Item::Table(table)
+            }
+            (_, unchanged) => {
+                *dep = unchanged;
+                continue;
+            }
+        };
+        *dep = new_dep;
+        changed = true;
+    }
+    if changed {
+        // Omit the header of a table that only holds tables, e.g. `[dependencies]` before
+        // `[dependencies.foo]`.
+        let has_values = deps.iter().any(|(_, dep)| !dep.is_table());
+        deps.set_implicit(!has_values);
+    }
+    changed
+}
+
+/// Return the whitespace and comments of a decor, or an empty string if it is not set.
+fn decor_str(raw: Option<&toml_edit::RawString>) -> String {
+    raw.and_then(|raw| raw.as_str())
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// A dependency entry to be written into a manifest.
+///
+/// A dependency with nothing but a version is written in the short form `dep = "1.0"`,
+/// otherwise as an inline table `dep = { version = "1.0", features = ["derive"] }`.
+#[derive(Clone, Debug)]
+pub struct DependencyBuilder {
+    version: Option<String>,
+    git: Option<String>,
+    branch: Option<String>,
+    tag: Option<String>,
+    rev: Option<String>,
+    path: Option<String>,
+    registry: Option<String>,
+    package: Option<String>,
+    features: Vec<String>,
+    optional: bool,
+    default_features: bool,
+}
+
+impl Default for DependencyBuilder {
+    fn default() -> Self {
+        DependencyBuilder {
+            version: None,
+            git: None,
+            branch: None,
+            tag: None,
+            rev: None,
+            path: None,
+            registry: None,
+            package: None,
+            features: Vec::new(),
+            optional: false,
+            default_features: true,
+        }
+    }
+}
+
+impl DependencyBuilder {
+    /// Create an empty dependency entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the version requirement, e.g. `1.0`.
+    pub fn version(mut self, req: &str) -> Self {
+        self.version = Some(req.to_owned());
+        self
+    }
+
+    /// Set the URL of the git repository to fetch the dependency from.
+    pub fn git(mut self, url: &str) -> Self {
+        self.git = Some(url.to_owned());
+        self
+    }
+
+    /// Set the branch of the git repository to use.
+    pub fn branch(mut self, branch: &str) -> Self {
+        self.branch = Some(branch.to_owned());
+        self
+    }
+
+    /// Set the tag of the git repository to use.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_owned());
+        self
+    }
+
+    /// Set the commit of the git repository to use.
+    pub fn rev(mut self, rev: &str) -> Self {
+        self.rev = Some(rev.to_owned());
+        self
+    }
+
+    /// Set the path of the dependency, relative to the manifest.
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_owned());
+        self
+    }
+
+    /// Set the registry to fetch the dependency from instead of crates.io.
+    pub fn registry(mut self, registry: &str) -> Self {
+        self.registry = Some(registry.to_owned());
+        self
+    }
+
+    /// Set the name of the crate when it differs from the name of the dependency.
+    pub fn package(mut self, package: &str) -> Self {
+        self.package = Some(package.to_owned());
+        self
+    }
+
+    /// Set the features to enable.
+    pub fn features(mut self, features: &[String]) -> Self {
+        self.features = features.to_vec();
+        self
+    }
+
+    /// Set whether the dependency is optional.
+    pub fn optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
+    }
+
+    /// Set whether the default features of the dependency are enabled.
+    pub fn default_features(mut self, default_features: bool) -> Self {
+        self.default_features = default_features;
+        self
+    }
+
+    /// Check that the entry names at most one source, i.e. one of `git`, `path` and
+    /// `registry`, that `branch`, `tag` and `rev` are only used, one at a time, with `git`,
+    /// and that it has a version unless it comes from `git` or `path`.
+    pub(crate) fn validate(&self) -> Result<(), ManifestError> {
+        let sources = [
+            ("git", &self.git),
+            ("path", &self.path),
+            ("registry", &self.registry),
+        ];
+        let mut given = sources.iter().filter(|(_, value)| value.is_some());
+        if let (Some(first), Some((key, value))) = (given.next(), given.next()) {
+            return Err(ManifestError::InvalidValue {
+                key: (*key).to_owned(),
+                value: value.as_deref().unwrap_or_default().to_owned(),
+                reason: format!("cannot be combined with `{}`", first.0),
+            });
+        }
+
+        let refs = [
+            ("branch", &self.branch),
+            ("tag", &self.tag),
+            ("rev", &self.rev),
+        ];
+        let mut given = refs.iter().filter(|(_, value)| value.is_some());
+        if let Some((key, value)) = given.next() {
+            let reason = if self.git.is_none() {
+                "requires `git`".to_owned()
+            } else if let Some((other, _)) = given.next() {
+                format!("cannot be combined with `{}`", other)
+            } else {
+                return Ok(());
+            };
+            return Err(ManifestError::InvalidValue {
+                key: (*key).to_owned(),
+                value: value.as_deref().unwrap_or_default().to_owned(),
+                reason,
+            });
+        }
+
+        if self.version.is_none() && self.git.is_none() && self.path.is_none() {
+            return Err(ManifestError::InvalidValue {
+                key: "version".to_owned(),
+                value: String::new(),
+                reason: "a version, `git` or `path` is required".to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn to_item(&self) -> toml_edit::Item {
+        let mut table = toml_edit::InlineTable::new();
+        let strings = [
+            ("package", &self.package),
+            ("version", &self.version),
+            ("registry", &self.registry),
+            ("git", &self.git),
+            ("branch", &self.branch),
+            ("tag", &self.tag),
+            ("rev", &self.rev),
+            ("path", &self.path),
+        ];
+        for (key, value) in strings.iter() {
+            if let Some(value) = value {
+                table.insert(*key, value.as_str().into());
+            }
+        }
+        if !self.features.is_empty() {
+            let features = self.features.iter().collect::<toml_edit::Array>();
+            table.insert("features", features.into());
+        }
+        if self.optional {
+            table.insert("optional", true.into());
+        }
+        if !self.default_features {
+            table.insert("default-features", false.into());
+        }
+
+        match &self.version {
+            Some(version) if table.len() == 1 => toml_edit::value(version.as_str()),
+            _ => toml_edit::value(table),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(req: &str, new_version: &str) -> String {
+        bump_requirement(req, &semver::Version::parse(new_version).unwrap())
+    }
+
+    #[test]
+    fn bump_requirement_keeps_operator_and_precision() {
+        assert_eq!(bump("~0.3", "0.4.0"), "~0.4");
+        assert_eq!(bump("=0.3.5", "0.3.6"), "=0.3.6");
+        assert_eq!(bump("^0.3", "0.4.0"), "^0.4");
+        assert_eq!(bump("0.3", "0.4.0"), "0.4");
+        assert_eq!(bump("0.3.*", "0.4.0"), "0.4.*");
+    }
+
+    #[test]
+    fn bump_requirement_falls_back_to_the_new_version() {
+        assert_eq!(bump(">=0.3, <0.4", "0.4.0"), "0.4.0");
+        assert_eq!(bump(">0.3", "0.4.0"), "0.4.0");
+    }
+}
@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// An error that occurred while loading, editing or writing manifests.
+#[derive(Debug)]
+pub enum ManifestError {
+    /// Reading or writing a manifest failed.
+    Io(io::Error),
+    /// A manifest is not a valid TOML document.
+    TomlParse {
+        path: PathBuf,
+        source: toml_edit::TomlError,
+    },
+    /// Running `cargo metadata` failed.
+    Metadata(cargo_metadata::Error),
+    /// A version string is not a valid semantic version.
+    SemVer(semver::SemVerError),
+    /// There is no workspace member with the given name.
+    PackageNotFound(String),
+    /// A value was rejected before it was written into a manifest.
+    InvalidValue {
+        key: String,
+        value: String,
+        reason: String,
+    },
+    /// An operation over several packages failed for the listed packages.
+    Multiple(Vec<(String, ManifestError)>),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io(e) => write!(f, "I/O error: {}", e),
+            ManifestError::TomlParse { path, source } => {
+                write!(f, "failed to parse {}: {}", path.display(), source)
+            }
+            ManifestError::Metadata(e) => write!(f, "failed to run `cargo metadata`: {}", e),
+            ManifestError::SemVer(e) => write!(f, "invalid version: {}", e),
+            ManifestError::PackageNotFound(name) => write!(f, "package `{}` not found", name),
+            ManifestError::InvalidValue { key, value, reason } => {
+                write!(f, "invalid value `{}` for `{}`: {}", value, key, reason)
+            }
+            ManifestError::Multiple(errors) => {
+                write!(f, "failed for {} package(s)", errors.len())?;
+                for (package, e) in errors {
+                    write!(f, "\n  `{}`: {}", package, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ManifestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ManifestError::Io(e) => Some(e),
+            ManifestError::TomlParse { source, .. } => Some(source),
+            ManifestError::Metadata(e) => Some(e),
+            ManifestError::SemVer(e) => Some(e),
+            ManifestError::PackageNotFound(_)
+            | ManifestError::InvalidValue { .. }
+            | ManifestError::Multiple(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ManifestError {
+    fn from(e: io::Error) -> Self {
+        ManifestError::Io(e)
+    }
+}
+
+impl From<cargo_metadata::Error> for ManifestError {
+    fn from(e: cargo_metadata::Error) -> Self {
+        ManifestError::Metadata(e)
+    }
+}
+
+impl From<semver::SemVerError> for ManifestError {
+    fn from(e: semver::SemVerError) -> Self {
+        ManifestError::SemVer(e)
+    }
+}
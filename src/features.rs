@@ -0,0 +1,212 @@
+//! Editing of the `[features]` table.
+
+use std::collections::BTreeMap;
+
+use crate::Metadata;
+
+impl Metadata {
+    /// Return `true` if `package` defines `feature` in `[features]`.
+    pub fn has_feature(&self, package: &str, feature: &str) -> bool {
+        self.raw_features(package)
+            .is_some_and(|features| features.contains_key(feature))
+    }
+
+    /// Return the names of the features defined in `[features]` of `package`.
+    /// Return `None` if the package does not exist.
+    pub fn features(&self, package: &str) -> Option<Vec<String>> {
+        self.manifest(package)?;
+        let names = self
+            .raw_features(package)
+            .map(|features| features.iter().map(|(name, _)| name.to_owned()).collect())
+            .unwrap_or_default();
+        Some(names)
+    }
+
+    /// Return each feature name defined in `[features]` of more than one workspace member,
+    /// sorted by name and paired with the names of those members.
+    ///
+    /// Shared names such as `default` or `std` are usually intended, so it is up to the
+    /// caller to decide which collisions are a problem.
+    pub fn feature_name_collisions(&self) -> Vec<(String, Vec<String>)> {
+        let mut definitions = BTreeMap::<String, Vec<String>>::new();
+        for name in self.workspace_members() {
+            for feature in self.features(name).unwrap_or_default() {
+                definitions
+                    .entry(feature)
+                    .or_default()
+                    .push(name.to_owned());
+            }
+        }
+        definitions
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .collect()
+    }
+
+    /// Set `feature = [...enables]` in `[features]` of `package`, creating the table if
+    /// needed. An existing feature with the same name is overwritten.
+    /// Return `None` if the package does not exist.
+    pub fn add_feature(&mut self, package: &str, feature: &str, enables: &[String]) -> Option<()> {
+        let _scope = self.edit_scope();
+        let mut raw_enables = enables.iter().collect::<toml_edit::Array>();
+        self.style.format_array(&mut raw_enables);
+        let features = self
+            .manifest_mut(package)?
+            .entry("features")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()?;
+        if crate::string_array(features.get(feature)).as_deref() == Some(enables) {
+            return Some(());
+        }
+        features.insert(feature, toml_edit::value(raw_enables));
+        self.mark_dirty(package);
+        self.record_edit(package, format!("features.{}", feature));
+        Some(())
+    }
+
+    /// Copy the features defined in `[features]` of `from` into `[features]` of `into`.
+    /// If `into` already defines a feature with the same name, the entries missing from its
+    /// list are appended rather than overwriting it.
+    /// Return `None` if either package does not exist.
+    pub fn merge_features(&mut self, from: &str, into: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.manifest(from)?;
+        let source = self
+            .raw_features(from)
+            .map(|features| {
+                features
+                    .iter()
+                    .map(|(name, enables)| {
+                        let enables = crate::string_array(Some(enables)).unwrap_or_default();
+                        (name.to_owned(), enables)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        self.manifest_mut(into)?;
+
+        for (feature, enables) in source {
+            let existing = self
+                .manifest_mut(into)?
+                .get_mut("features")
+                .and_then(|features| features.as_table_like_mut())
+                .and_then(|features| features.get_mut(&feature))
+                .and_then(|existing| existing.as_array_mut());
+            let existing = match existing {
+                Some(existing) => existing,
+                None => {
+                    self.add_feature(into, &feature, &enables)?;
+                    continue;
+                }
+            };
+            let mut changed = false;
+            for enabled in &enables {
+                if existing
+                    .iter()
+                    .all(|e| e.as_str() != Some(enabled.as_str()))
+                {
+                    existing.push(enabled.as_str());
+                    changed = true;
+                }
+            }
+            if changed {
+                self.mark_dirty(into);
+                self.record_edit(into, format!("features.{}", feature));
+            }
+        }
+        Some(())
+    }
+
+    /// Remove `feature` from `[features]` of `package`.
+    /// Return `true` if the feature was removed.
+    pub fn remove_feature(&mut self, package: &str, feature: &str) -> bool {
+        let _scope = self.edit_scope();
+        self.remove_feature_with(package, feature, false)
+    }
+
+    /// Remove `feature` from `[features]` of `package`.
+    /// If `scrub_references` is `true`, `feature` is also removed from the lists of the
+    /// other features that enable it.
+    /// Return `true` if the feature was removed.
+    pub fn remove_feature_with(
+        &mut self,
+        package: &str,
+        feature: &str,
+        scrub_references: bool,
+    ) -> bool {
+        let _scope = self.edit_scope();
+        let features = match self
+            .manifest_mut(package)
+            .and_then(|raw_map| raw_map.get_mut("features"))
+            .and_then(|features| features.as_table_like_mut())
+        {
+            Some(features) => features,
+            None => return false,
+        };
+        if features.remove(feature).is_none() {
+            return false;
+        }
+        let mut scrubbed = Vec::new();
+        if scrub_references {
+            for (name, enables) in features.iter_mut() {
+                if let Some(enables) = enables.as_array_mut() {
+                    let len = enables.len();
+                    enables.retain(|enabled| enabled.as_str() != Some(feature));
+                    if enables.len() != len {
+                        scrubbed.push(name.get().to_owned());
+                    }
+                }
+            }
+        }
+        self.mark_dirty(package);
+        self.record_edit(package, format!("features.{}", feature));
+        for name in scrubbed {
+            self.record_edit(package, format!("features.{}", name));
+        }
+        true
+    }
+
+    fn raw_features(&self, package: &str) -> Option<&dyn toml_edit::TableLike> {
+        self.manifest(package)?.get("features")?.as_table_like()
+    }
+}
+
+/// Make the entries of `[features]` in `doc` that refer to the dependency `old` refer to
+/// `new` after the dependency was renamed: `dep:old`, `old/feature`, `old?/feature`, and
+/// `old` itself unless `old` is also the name of a feature.
+/// Return `true` if anything changed.
+pub(crate) fn rename_dependency(doc: &mut toml_edit::DocumentMut, old: &str, new: &str) -> bool {
+    let features = match doc
+        .get_mut("features")
+        .and_then(|features| features.as_table_like_mut())
+    {
+        Some(features) => features,
+        None => return false,
+    };
+    let mut changed = false;
+    let is_feature = features.contains_key(old);
+    for (_, enables) in features.iter_mut() {
+        let enables = match enables.as_array_mut() {
+            Some(enables) => enables,
+            None => continue,
+        };
+        for enabled in enables.iter_mut() {
+            let renamed = match enabled.as_str() {
+                Some(name) if name == old && !is_feature => new.to_owned(),
+                Some(name) if name.strip_prefix("dep:") == Some(old) => format!("dep:{}", new),
+                Some(name) => match name.strip_prefix(old) {
+                    Some(rest) if rest.starts_with('/') || rest.starts_with("?/") => {
+                        format!("{}{}", new, rest)
+                    }
+                    _ => continue,
+                },
+                None => continue,
+            };
+            let decor = enabled.decor().clone();
+            *enabled = renamed.into();
+            *enabled.decor_mut() = decor;
+            changed = true;
+        }
+    }
+    changed
+}
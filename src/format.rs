@@ -0,0 +1,60 @@
+//! Layout details of a manifest file that are not part of the TOML document.
+
+/// The line ending of the written manifests.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Use the line ending that is most common in the original file.
+    #[default]
+    Auto,
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+}
+
+/// How a manifest was laid out on disk, reproduced when it is written back.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct FileFormat {
+    trailing_newline: bool,
+    crlf: bool,
+}
+
+impl FileFormat {
+    /// Detect the format of the manifest with the given content, using `line_ending` unless
+    /// it is `Auto`.
+    pub(crate) fn detect(content: &str, line_ending: LineEnding) -> Self {
+        let crlf = match line_ending {
+            LineEnding::Auto => {
+                let crlf_count = content.matches("\r\n").count();
+                crlf_count > content.matches('\n').count() - crlf_count
+            }
+            LineEnding::Lf => false,
+            LineEnding::Crlf => true,
+        };
+        FileFormat {
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+            crlf,
+        }
+    }
+
+    /// Lay out the rendered document `content` like the original file.
+    pub(crate) fn apply(self, mut content: String) -> String {
+        if self.trailing_newline {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+        } else {
+            while content.ends_with('\n') {
+                content.pop();
+                if content.ends_with('\r') {
+                    content.pop();
+                }
+            }
+        }
+        // `toml_edit` renders every line ending as `\n`.
+        if self.crlf {
+            content = content.replace("\r\n", "\n").replace('\n', "\r\n");
+        }
+        content
+    }
+}
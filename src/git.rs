@@ -0,0 +1,139 @@
+//! Staging of the written manifests with the `git` binary.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{BumpLevel, Metadata};
+
+impl Metadata {
+    /// Run `git add` in `repo_root` for the manifests written by the last `dump`.
+    /// Nothing is run if no manifest was written.
+    ///
+    /// Return an error if `git` cannot be run or exits with a failure.
+    pub fn git_add_modified(&self, repo_root: &Path) -> io::Result<()> {
+        if self.written.is_empty() {
+            return Ok(());
+        }
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(repo_root)
+            .arg("add")
+            .arg("--")
+            .args(&self.written)
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "`git add` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    /// Bump the given part of the version of the workspace members with files changed since
+    /// `git_ref`, as reported by `git diff --name-only` in the workspace root.
+    ///
+    /// A changed file belongs to the member with the innermost directory containing it, so
+    /// a file of a member nested in another member's directory only bumps the nested one.
+    /// Return the names of the bumped members, see `bump_with`.
+    ///
+    /// Return an error if `git_ref` does not name a commit, or if `git` cannot be run or
+    /// exits with a failure.
+    pub fn bump_changed_since(
+        &mut self,
+        git_ref: &str,
+        level: BumpLevel,
+    ) -> io::Result<Vec<String>> {
+        let _scope = self.edit_scope();
+        let workspace_root = &self.metadata.workspace_root;
+        // Resolve the ref first so that it can never be taken for an option of `git diff`.
+        let commit = git_output(
+            workspace_root,
+            &[
+                "rev-parse",
+                "--verify",
+                "--end-of-options",
+                &format!("{}^{{commit}}", git_ref),
+            ],
+        )?;
+        let stdout = git_output(
+            workspace_root,
+            &["diff", "--name-only", "--relative", commit.trim(), "--"],
+        )?;
+
+        let member_dirs = self
+            .metadata
+            .packages
+            .iter()
+            .filter(|p| self.is_member(&p.id))
+            .filter_map(|p| {
+                let dir = p
+                    .manifest_path
+                    .parent()?
+                    .strip_prefix(workspace_root)
+                    .ok()?;
+                Some((p.name.clone(), dir.to_path_buf()))
+            })
+            .collect::<Vec<_>>();
+        let changed = stdout
+            .lines()
+            .filter_map(|file| owner(&member_dirs, Path::new(file)))
+            .collect::<HashSet<_>>();
+
+        let bumped = self.bump_with(|name, _| {
+            if changed.contains(name) {
+                Some(level)
+            } else {
+                None
+            }
+        });
+        Ok(bumped.into_iter().map(|(name, _)| name).collect())
+    }
+}
+
+/// Run `git` in `dir` with `args` and return its standard output.
+fn git_output(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "`git {}` failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Return the name of the member whose directory is the innermost one containing `file`.
+fn owner<'a>(member_dirs: &'a [(String, PathBuf)], file: &Path) -> Option<&'a str> {
+    member_dirs
+        .iter()
+        .filter(|(_, dir)| file.starts_with(dir))
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(name, _)| name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_is_innermost_member() {
+        let member_dirs = vec![
+            ("root".to_owned(), PathBuf::new()),
+            ("foo".to_owned(), PathBuf::from("crates/foo")),
+            ("foo-bar".to_owned(), PathBuf::from("crates/foo-bar")),
+            ("nested".to_owned(), PathBuf::from("crates/foo/nested")),
+        ];
+        let owner = |file: &str| owner(&member_dirs, Path::new(file));
+
+        assert_eq!(owner("src/lib.rs"), Some("root"));
+        assert_eq!(owner("crates/foo/src/lib.rs"), Some("foo"));
+        assert_eq!(owner("crates/foo-bar/src/lib.rs"), Some("foo-bar"));
+        assert_eq!(owner("crates/foo/nested/Cargo.toml"), Some("nested"));
+        assert_eq!(owner("crates/foo/nestedness.txt"), Some("foo"));
+    }
+}
@@ -0,0 +1,246 @@
+//! Undoing and redoing the edits made by the public methods of `Metadata`.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use cargo_metadata::PackageId;
+
+use crate::{Change, Metadata};
+
+/// The number of states kept for `undo`.
+const UNDO_LIMIT: usize = 100;
+
+/// The states `undo` and `redo` move between.
+#[derive(Default)]
+pub(crate) struct History {
+    /// The number of nested edit scopes that are open.
+    depth: usize,
+    /// Incremented whenever a manifest is marked as modified.
+    revision: u64,
+    /// The revision when the outermost open edit scope was opened.
+    start_revision: u64,
+    /// The state before the outermost open edit scope, followed by the state before each
+    /// open `transaction`.
+    frames: Vec<Snapshot>,
+    /// States before each edit, the most recent last.
+    undo_stack: VecDeque<Snapshot>,
+    /// States left by `undo`, the most recently undone last.
+    redo_stack: Vec<Snapshot>,
+}
+
+/// What a `Metadata` looked like before some edits. Only the parts the edits touched are
+/// saved, each the first time it is about to change.
+#[derive(Default)]
+pub(crate) struct Snapshot {
+    /// The manifests of workspace members, keyed by package id.
+    manifests: HashMap<PackageId, toml_edit::DocumentMut>,
+    /// The root manifest of a virtual workspace.
+    virtual_manifest: Option<toml_edit::DocumentMut>,
+    /// The names of the workspace members renamed by `rename_package`.
+    names: HashMap<PackageId, String>,
+    /// The bookkeeping of the modified manifests, saved along with the first manifest.
+    state: Option<State>,
+}
+
+/// The bookkeeping of the modified manifests.
+struct State {
+    dirty: HashSet<PackageId>,
+    virtual_manifest_dirty: bool,
+    changes: Vec<Change>,
+}
+
+/// Tracks the edits of a public method, so that `undo` can revert the whole call. Every
+/// public method that may edit a manifest opens one with `Metadata::edit_scope` before
+/// touching anything; only the outermost scope of nested calls is kept, and only if a
+/// manifest was marked as modified before it closes.
+pub(crate) struct EditScope {
+    history: Arc<Mutex<History>>,
+}
+
+impl Drop for EditScope {
+    fn drop(&mut self) {
+        let mut history = lock(&self.history);
+        history.depth -= 1;
+        if history.depth > 0 {
+            return;
+        }
+        let snapshot = history.frames.drain(..).next();
+        if let Some(snapshot) = snapshot {
+            if history.revision != history.start_revision {
+                if history.undo_stack.len() == UNDO_LIMIT {
+                    history.undo_stack.pop_front();
+                }
+                history.undo_stack.push_back(snapshot);
+                history.redo_stack.clear();
+            }
+        }
+    }
+}
+
+impl Metadata {
+    /// Revert the last call of a method that edited the manifests, including what `dump`
+    /// would write and `pending_changes` reports. Return `false` if there is nothing to undo.
+    ///
+    /// Each call is undone as a whole, e.g. `bump_all_patch_versions` or a `transaction`.
+    /// The last 100 calls since the manifests were loaded or last written by `dump` can be
+    /// undone.
+    pub fn undo(&mut self) -> bool {
+        let snapshot = match self.history().undo_stack.pop_back() {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+        let current = self.restore(snapshot);
+        self.history().redo_stack.push(current);
+        true
+    }
+
+    /// Reapply the edits reverted by the last `undo`.
+    /// Return `false` if there is nothing to redo, including after a new edit.
+    pub fn redo(&mut self) -> bool {
+        let snapshot = match self.history().redo_stack.pop() {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+        let current = self.restore(snapshot);
+        self.history().undo_stack.push_back(current);
+        true
+    }
+
+    /// Open an edit scope, see `EditScope`. Keep it until the method returns.
+    pub(crate) fn edit_scope(&self) -> EditScope {
+        let mut history = self.history();
+        if history.depth == 0 {
+            history.frames = vec![Snapshot::default()];
+            history.start_revision = history.revision;
+        }
+        history.depth += 1;
+        EditScope {
+            history: Arc::clone(&self.history),
+        }
+    }
+
+    /// Start saving the state for a `transaction` to roll back to, in addition to the
+    /// state saved for `undo`. Call within an edit scope.
+    pub(crate) fn begin_savepoint(&self) {
+        self.history().frames.push(Snapshot::default());
+    }
+
+    /// Return the state saved since the matching `begin_savepoint`.
+    pub(crate) fn end_savepoint(&self) -> Snapshot {
+        self.history().frames.pop().unwrap_or_default()
+    }
+
+    /// Save the manifest of the workspace member with the given id before it is edited.
+    pub(crate) fn save_manifest(&self, package_id: &PackageId) {
+        let manifest = match self.raw_toml_map.get(package_id) {
+            Some(manifest) => manifest,
+            None => return,
+        };
+        for frame in self.history().frames.iter_mut() {
+            self.save_state(frame);
+            if !frame.manifests.contains_key(package_id) {
+                frame.manifests.insert(package_id.clone(), manifest.clone());
+            }
+        }
+    }
+
+    /// Save the root manifest of a virtual workspace before it is edited.
+    pub(crate) fn save_virtual_manifest(&self) {
+        let manifest = match &self.virtual_manifest {
+            Some(manifest) => manifest,
+            None => return,
+        };
+        for frame in self.history().frames.iter_mut() {
+            self.save_state(frame);
+            if frame.virtual_manifest.is_none() {
+                frame.virtual_manifest = Some(manifest.clone());
+            }
+        }
+    }
+
+    /// Save the name of the workspace member with the given id before it is renamed.
+    pub(crate) fn save_name(&self, package_id: &PackageId) {
+        let name = match self.metadata.packages.iter().find(|p| p.id == *package_id) {
+            Some(p) => &p.name,
+            None => return,
+        };
+        for frame in self.history().frames.iter_mut() {
+            frame
+                .names
+                .entry(package_id.clone())
+                .or_insert_with(|| name.clone());
+        }
+    }
+
+    /// Put back the parts saved in `snapshot`, and return what they were before.
+    pub(crate) fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let mut previous = Snapshot::default();
+        for (package_id, manifest) in snapshot.manifests {
+            if let Some(current) = self.raw_toml_map.insert(package_id.clone(), manifest) {
+                previous.manifests.insert(package_id, current);
+            }
+        }
+        if let Some(manifest) = snapshot.virtual_manifest {
+            previous.virtual_manifest = self.virtual_manifest.replace(manifest);
+        }
+        for p in &mut self.metadata.packages {
+            if let Some(name) = snapshot.names.get(&p.id) {
+                let current = mem::replace(&mut p.name, name.clone());
+                previous.names.insert(p.id.clone(), current);
+            }
+        }
+        if let Some(state) = snapshot.state {
+            previous.state = Some(State {
+                dirty: mem::replace(&mut self.dirty, state.dirty),
+                virtual_manifest_dirty: mem::replace(
+                    &mut self.virtual_manifest_dirty,
+                    state.virtual_manifest_dirty,
+                ),
+                changes: mem::replace(&mut self.changes, state.changes),
+            });
+        }
+        previous
+    }
+
+    /// Remember that a manifest was modified, so that the open edit scope is kept.
+    pub(crate) fn bump_revision(&self) {
+        self.history().revision += 1;
+    }
+
+    /// Return the current revision, see `bump_revision`.
+    pub(crate) fn revision(&self) -> u64 {
+        self.history().revision
+    }
+
+    /// Forget the states to undo and redo, e.g. after the manifests were written.
+    pub(crate) fn clear_history(&self) {
+        let mut history = self.history();
+        history.undo_stack.clear();
+        history.redo_stack.clear();
+    }
+
+    /// Forget the edits made since `revision`, after they were rolled back.
+    pub(crate) fn reset_revision(&self, revision: u64) {
+        self.history().revision = revision;
+    }
+
+    /// Save the bookkeeping into `frame` unless it already has it.
+    fn save_state(&self, frame: &mut Snapshot) {
+        if frame.state.is_none() {
+            frame.state = Some(State {
+                dirty: self.dirty.clone(),
+                virtual_manifest_dirty: self.virtual_manifest_dirty,
+                changes: self.changes.clone(),
+            });
+        }
+    }
+
+    fn history(&self) -> MutexGuard<'_, History> {
+        lock(&self.history)
+    }
+}
+
+fn lock(history: &Mutex<History>) -> MutexGuard<'_, History> {
+    history.lock().unwrap_or_else(PoisonError::into_inner)
+}
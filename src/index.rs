@@ -0,0 +1,171 @@
+//! Checks of dependency requirements against the crates.io index.
+
+use std::io;
+
+use crate::dependency;
+use crate::{ManifestError, Metadata};
+
+/// The sparse index of crates.io.
+const INDEX_URL: &str = "https://index.crates.io";
+
+impl Metadata {
+    /// Set the requirement of the dependency `dep` in `[dependencies]` of `package` to `req`
+    /// like `set_dependency_version`, after checking `req` against the crates.io index.
+    ///
+    /// Return an error if `req` pins an exact version, e.g. `=1.2.3`, that is yanked or not
+    /// published, or if every published version matching `req` is yanked. Dependencies from
+    /// `git`, a `path` or another registry are not checked.
+    /// Return an error if either the package or the dependency does not exist, if the
+    /// dependency is inherited with `dep.workspace = true`, or if the index cannot be fetched.
+    pub fn set_dependency_version_checked(
+        &mut self,
+        package: &str,
+        dep: &str,
+        req: &str,
+    ) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        let raw_dep = self
+            .manifest(package)
+            .filter(|_| self.contains_package(package))
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))?
+            .get("dependencies")
+            .and_then(|deps| deps.as_table_like())
+            .and_then(|deps| deps.get(dep))
+            .ok_or_else(|| ManifestError::InvalidValue {
+                key: "dependencies".to_owned(),
+                value: dep.to_owned(),
+                reason: "no such dependency".to_owned(),
+            })?;
+        if crate::is_inherited(raw_dep) {
+            return Err(ManifestError::InvalidValue {
+                key: format!("dependencies.{}", dep),
+                value: req.to_owned(),
+                reason: "is inherited from `[workspace.dependencies]`".to_owned(),
+            });
+        }
+        let is_crates_io = ["git", "path", "registry"]
+            .iter()
+            .all(|key| raw_dep.get(key).is_none());
+        if is_crates_io {
+            let crate_name = dependency::crate_name(dep, raw_dep).to_owned();
+            check_not_yanked(&crate_name, req)?;
+        }
+        self.set_dependency_version(package, dep, req);
+        Ok(())
+    }
+}
+
+fn check_not_yanked(crate_name: &str, req: &str) -> Result<(), ManifestError> {
+    let version_req = semver::VersionReq::parse(req).map_err(|e| ManifestError::InvalidValue {
+        key: format!("dependencies.{}", crate_name),
+        value: req.to_owned(),
+        reason: e.to_string(),
+    })?;
+    let versions = fetch_versions(crate_name)?;
+    check_versions(req, &version_req, &versions).map_err(|reason| ManifestError::InvalidValue {
+        key: format!("dependencies.{}", crate_name),
+        value: req.to_owned(),
+        reason,
+    })
+}
+
+/// Check `req`, parsed as `version_req`, against the published `versions` and whether each
+/// is yanked. Return the reason `req` is rejected, if any.
+///
+/// A partial exact requirement such as `=1.2` is checked like a range, since it matches
+/// every `1.2.x` as in Cargo.
+fn check_versions(
+    req: &str,
+    version_req: &semver::VersionReq,
+    versions: &[(semver::Version, bool)],
+) -> Result<(), String> {
+    let exact = req
+        .trim()
+        .strip_prefix('=')
+        .and_then(|exact| semver::Version::parse(exact.trim()).ok());
+    if let Some(exact) = exact {
+        return match versions.iter().find(|(version, _)| *version == exact) {
+            Some((_, false)) => Ok(()),
+            Some((_, true)) => Err(format!("version {} is yanked", exact)),
+            None => Err(format!("version {} is not published", exact)),
+        };
+    }
+    let mut matching = versions
+        .iter()
+        .filter(|(version, _)| version_req.matches(version))
+        .peekable();
+    if matching.peek().is_none() {
+        return Err("no published version matches".to_owned());
+    }
+    if matching.all(|(_, yanked)| *yanked) {
+        return Err("every matching version is yanked".to_owned());
+    }
+    Ok(())
+}
+
+/// Return the published versions of `crate_name` and whether each is yanked.
+fn fetch_versions(crate_name: &str) -> Result<Vec<(semver::Version, bool)>, ManifestError> {
+    // The index only has ASCII names, and the prefix below is sliced by bytes.
+    if !crate_name.is_ascii() {
+        return Err(ManifestError::InvalidValue {
+            key: format!("dependencies.{}", crate_name),
+            value: crate_name.to_owned(),
+            reason: "is not a valid crate name: only ASCII characters are allowed".to_owned(),
+        });
+    }
+    let name = crate_name.to_lowercase();
+    let prefix = match name.len() {
+        1 => "1".to_owned(),
+        2 => "2".to_owned(),
+        3 => format!("3/{}", &name[..1]),
+        _ => format!("{}/{}", &name[..2], &name[2..4]),
+    };
+    let url = format!("{}/{}/{}", INDEX_URL, prefix, name);
+    let body = ureq::get(&url)
+        .call()
+        .map_err(|e| io::Error::other(format!("failed to fetch {}: {}", url, e)))?
+        .into_string()?;
+
+    // Each line of the index file describes one version.
+    Ok(body
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|entry| {
+            let version = semver::Version::parse(entry.get("vers")?.as_str()?).ok()?;
+            let yanked = entry.get("yanked")?.as_bool()?;
+            Some((version, yanked))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_versions, fetch_versions};
+
+    fn check(req: &str, versions: &[(&str, bool)]) -> Result<(), String> {
+        let versions = versions
+            .iter()
+            .map(|(version, yanked)| (semver::Version::parse(version).unwrap(), *yanked))
+            .collect::<Vec<_>>();
+        check_versions(req, &semver::VersionReq::parse(req).unwrap(), &versions)
+    }
+
+    #[test]
+    fn partial_exact_requirement_is_a_range() {
+        let versions = [("1.2.0", true), ("1.2.1", false), ("1.3.0", false)];
+        assert_eq!(check("=1.2", &versions), Ok(()));
+        assert_eq!(
+            check("=1.2", &[("1.2.0", true), ("1.3.0", false)]),
+            Err("every matching version is yanked".to_owned())
+        );
+        assert_eq!(
+            check("=1.2.0", &versions),
+            Err("version 1.2.0 is yanked".to_owned())
+        );
+    }
+
+    #[test]
+    fn non_ascii_crate_name_is_an_error() {
+        assert!(fetch_versions("é1").is_err());
+    }
+}
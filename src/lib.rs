@@ -1,141 +1,2798 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::format::FileFormat;
+use crate::history::History;
 use std::fs;
-use std::io;
-use std::io::Write;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+mod change;
+mod config;
+pub mod conventional;
+mod crates_io;
+mod dependency;
+mod error;
+mod features;
+mod format;
+#[cfg(feature = "git")]
+mod git;
+mod history;
+#[cfg(feature = "index")]
+mod index;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod plan;
+mod profile;
+mod single;
+mod style;
+mod validate;
+mod workspace;
+
+pub use crate::change::Change;
+pub use crate::dependency::{DepStyle, DependencyBuilder, DependencyKind};
+pub use crate::error::ManifestError;
+pub use crate::format::LineEnding;
+pub use crate::plan::BumpPlan;
+pub use crate::single::SingleManifest;
+pub use crate::style::TomlStyle;
+pub use crate::validate::ValidationError;
+pub use toml_edit;
 
 /// Meta-information of a cargo project.
+///
+/// Manifests are edited as `toml_edit` documents, so everything that is not edited is
+/// written back byte for byte: comments, key order, inline formatting and the order of
+/// arrays of tables such as `[[bin]]`, `[[example]]`, `[[bench]]` and `[[test]]`.
 pub struct Metadata {
-    raw_toml_map: HashMap<cargo_metadata::PackageId, toml::Value>,
+    /// The manifests of the workspace members.
+    raw_toml_map: HashMap<cargo_metadata::PackageId, toml_edit::DocumentMut>,
+    /// The manifests of the other packages, e.g. registry dependencies, which are only
+    /// read and never edited.
+    dependency_manifests: HashMap<cargo_metadata::PackageId, LazyManifest>,
     metadata: cargo_metadata::Metadata,
+    /// Packages whose raw TOML was modified since the last `dump`.
+    dirty: HashSet<cargo_metadata::PackageId>,
+    /// The root manifest of a virtual workspace, which does not belong to any package.
+    virtual_manifest: Option<toml_edit::DocumentMut>,
+    virtual_manifest_dirty: bool,
+    /// Layout of each manifest on disk, keyed by the manifest path.
+    formats: HashMap<PathBuf, FileFormat>,
+    /// Edits made since the last `dump`, reported by `pending_changes`.
+    changes: Vec<Change>,
+    /// Manifests written by the last `dump`.
+    written: Vec<PathBuf>,
+    /// The file name of the manifests, see `MetadataOptions`.
+    file_name: String,
+    /// Layout of the values written by the editing methods.
+    style: TomlStyle,
+    /// States for `undo` and `redo`, shared with the open edit scopes.
+    history: Arc<Mutex<History>>,
+}
+
+/// Options for `Metadata::from_dir_with_options`: the flags passed to `cargo metadata`, and
+/// which files are read and written as manifests.
+#[derive(Clone, Debug)]
+pub struct MetadataOptions {
+    /// Pass `--no-deps`, so that dependencies are not resolved, see `from_dir_no_deps`.
+    pub no_deps: bool,
+    /// Pass `--offline`, so that the network is not accessed.
+    pub offline: bool,
+    /// Pass `--frozen`, i.e. both `--locked` and `--offline`.
+    pub frozen: bool,
+    /// Pass `--locked`, so that `Cargo.lock` must be up to date.
+    pub locked: bool,
+    /// The file name of the manifests, `Cargo.toml` by default. Use e.g. `Cargo.toml.orig`
+    /// to edit the manifests vendored crates keep next to their normalized `Cargo.toml`.
+    pub file_name: String,
+    /// The line ending of the written manifests, by default the one most common in each
+    /// original file.
+    pub line_ending: LineEnding,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        MetadataOptions {
+            no_deps: false,
+            offline: false,
+            frozen: false,
+            locked: false,
+            file_name: "Cargo.toml".to_owned(),
+            line_ending: LineEnding::Auto,
+        }
+    }
+}
+
+impl MetadataOptions {
+    fn command(&self) -> cargo_metadata::MetadataCommand {
+        let mut command = cargo_metadata::MetadataCommand::new();
+        if self.no_deps {
+            command.no_deps();
+        }
+        let flags = [
+            ("--offline", self.offline),
+            ("--frozen", self.frozen),
+            ("--locked", self.locked),
+        ];
+        command.other_options(
+            flags
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(flag, _)| (*flag).to_owned())
+                .collect::<Vec<_>>(),
+        );
+        command
+    }
+}
+
+/// The edition and the minimum supported Rust version of a package.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToolchainReq {
+    /// `edition` in `[package]`, e.g. `2021`.
+    pub edition: Option<String>,
+    /// `rust-version` in `[package]`, e.g. `1.70.0`.
+    pub rust_version: Option<semver::Version>,
+}
+
+/// How the license of a package is declared, returned by `Metadata::license`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum License {
+    /// `license`, an SPDX expression such as `MIT OR Apache-2.0`.
+    Expression(String),
+    /// `license-file`, the path of a file holding a non-standard license.
+    File(String),
+}
+
+/// Counts describing a workspace, returned by `Metadata::summary`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkspaceSummary {
+    /// The number of packages known to `cargo metadata`, including dependencies.
+    pub packages: usize,
+    /// The number of workspace members.
+    pub members: usize,
+    /// The number of members whose version is a pre-release, e.g. `1.0.0-beta.1`.
+    pub prerelease_members: usize,
+    /// The number of members without `description`.
+    pub missing_description: usize,
+    /// The number of members with neither `license` nor `license-file`.
+    pub missing_license: usize,
 }
 
-#[derive(Copy, Clone, Debug)]
-enum SemVer {
+/// Editions known to Cargo.
+const EDITIONS: [&str; 4] = ["2015", "2018", "2021", "2024"];
+
+/// The part of a version to bump.
+///
+/// With the `serde` feature, it is deserialized from `"major"`, `"minor"`, `"patch"` or
+/// `"prerelease"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum BumpLevel {
+    /// `1.2.3` -> `2.0.0`
     Major,
+    /// `1.2.3` -> `1.3.0`
     Minor,
+    /// `1.2.3` -> `1.2.4`
     Patch,
+    /// `1.2.3-beta.1` -> `1.2.3-beta.2`
+    Prerelease,
+}
+
+/// Options that control how a version is bumped.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BumpOptions {
+    /// Keep the build metadata across the bump, e.g. `1.2.3+build5` becomes
+    /// `1.2.4+build5` instead of `1.2.4`.
+    pub preserve_build_metadata: bool,
 }
 
 impl Metadata {
     /// Create a `Metadata` a project at the given directory.
-    pub fn from_dir<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, ManifestError> {
+        Self::from_dir_with_options(dir, MetadataOptions::default())
+    }
+
+    /// Create a `Metadata` a project at the given directory without resolving dependencies.
+    ///
+    /// This passes `--no-deps` to `cargo metadata`, so neither the network nor the registry
+    /// cache is needed. Only the workspace members are known to the resulting `Metadata`.
+    pub fn from_dir_no_deps<P: AsRef<Path>>(dir: P) -> Result<Self, ManifestError> {
+        let options = MetadataOptions {
+            no_deps: true,
+            ..MetadataOptions::default()
+        };
+        Self::from_dir_with_options(dir, options)
+    }
+
+    /// Create a `Metadata` a project at the given directory like `from_dir`, running
+    /// `cargo metadata` and reading and writing the manifests as configured by `options`.
+    pub fn from_dir_with_options<P: AsRef<Path>>(
+        dir: P,
+        options: MetadataOptions,
+    ) -> Result<Self, ManifestError> {
+        let metadata = options.command().current_dir(dir.as_ref()).exec()?;
+        Self::from_metadata_and_options(metadata, options)
+    }
+
+    /// Create a `Metadata` of a project with the given `Cargo.toml`.
+    pub fn from_manifest_path<P: AsRef<Path>>(path: P) -> Result<Self, ManifestError> {
         let metadata = cargo_metadata::MetadataCommand::new()
-            .current_dir(dir)
-            .exec()
-            .map_err(io_error_other)?;
+            .manifest_path(path)
+            .exec()?;
+        Self::from_metadata(metadata)
+    }
+
+    /// Create a `Metadata` from the output of `cargo metadata` obtained elsewhere.
+    /// Only the manifests of the packages are read; `cargo metadata` is not run again.
+    ///
+    /// The manifests of the workspace members are read up front. Those of the other
+    /// packages, e.g. registry dependencies, are read the first time they are looked at.
+    pub fn from_metadata(metadata: cargo_metadata::Metadata) -> Result<Self, ManifestError> {
+        Self::from_metadata_and_options(metadata, MetadataOptions::default())
+    }
+
+    /// Create a `Metadata` from `metadata`, reading and writing the manifests as configured
+    /// by `options`. The flags for `cargo metadata` in `options` are not used.
+    fn from_metadata_and_options(
+        mut metadata: cargo_metadata::Metadata,
+        options: MetadataOptions,
+    ) -> Result<Self, ManifestError> {
+        // Redirect the manifests of the workspace members to the configured file in the
+        // same directory.
+        for p in &mut metadata.packages {
+            if metadata.workspace_members.contains(&p.id) {
+                p.manifest_path.set_file_name(&options.file_name);
+            }
+        }
+
+        let mut formats = HashMap::new();
+        let mut raw_toml_map = HashMap::new();
+        let mut dependency_manifests = HashMap::new();
+        for p in &metadata.packages {
+            if metadata.workspace_members.contains(&p.id) {
+                let (raw_value, format) = read_manifest(&p.manifest_path, options.line_ending)?;
+                raw_toml_map.insert(p.id.clone(), raw_value);
+                formats.insert(p.manifest_path.clone(), format);
+            } else {
+                dependency_manifests.insert(p.id.clone(), LazyManifest::new(&p.manifest_path));
+            }
+        }
 
-        let raw_toml_map = metadata
+        let root_manifest_path = metadata.workspace_root.join(&options.file_name);
+        let virtual_manifest = if metadata
             .packages
             .iter()
-            .map(|p| {
-                let content = fs::read_to_string(&p.manifest_path)?;
-                let raw_value = content.parse::<toml::Value>().map_err(io_error_other);
-                raw_value.map(|v| (p.id.clone(), v))
-            })
-            .collect::<Result<_, _>>()?;
+            .any(|p| p.manifest_path == root_manifest_path)
+        {
+            None
+        } else {
+            let (raw_value, format) = read_manifest(&root_manifest_path, options.line_ending)?;
+            formats.insert(root_manifest_path, format);
+            Some(raw_value)
+        };
 
         Ok(Metadata {
             metadata,
             raw_toml_map,
+            dependency_manifests,
+            dirty: HashSet::new(),
+            virtual_manifest,
+            virtual_manifest_dirty: false,
+            formats,
+            changes: Vec::new(),
+            written: Vec::new(),
+            file_name: options.file_name,
+            style: TomlStyle::default(),
+            history: Arc::default(),
         })
     }
 
-    /// Bump the patch version of all packages.
-    pub fn bump_all_patch_versions(&mut self) {
-        for (_, mut raw_map) in &mut self.raw_toml_map {
-            if let Some(version) = Self::get_version_mut(&mut raw_map) {
-                let mut ver = semver::Version::parse(version.as_str().unwrap()).unwrap();
-                ver.increment_patch();
-                *version = toml::Value::String(ver.to_string());
+    /// Return the names of all packages, including dependencies outside the workspace.
+    pub fn package_names(&self) -> impl Iterator<Item = &str> {
+        self.metadata.packages.iter().map(|p| p.name.as_str())
+    }
+
+    /// Return the names of the workspace members.
+    ///
+    /// Only workspace members can be edited; the manifests of other packages live
+    /// outside the workspace (e.g. in the registry cache).
+    pub fn workspace_members(&self) -> impl Iterator<Item = &str> {
+        self.metadata
+            .packages
+            .iter()
+            .filter(move |p| self.is_member(&p.id))
+            .map(|p| p.name.as_str())
+    }
+
+    /// Return the names shared by more than one workspace member, e.g. two crates at
+    /// different paths that are both called `utils`.
+    ///
+    /// The methods that take a package name edit only one of the members with that name,
+    /// so use the `_by_id` methods for them instead.
+    pub fn duplicate_member_names(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for name in self.workspace_members() {
+            if !seen.insert(name) && !duplicates.contains(&name) {
+                duplicates.push(name);
+            }
+        }
+        duplicates
+    }
+
+    /// Return `true` if there is a workspace member with the given name, i.e. a package
+    /// the editing methods can modify.
+    pub fn contains_package(&self, name: &str) -> bool {
+        self.member_id(name).is_some()
+    }
+
+    /// Return counts describing the workspace, e.g. for a dashboard.
+    ///
+    /// Keys inherited from the workspace with `key.workspace = true` count as present.
+    pub fn summary(&self) -> WorkspaceSummary {
+        let mut summary = WorkspaceSummary {
+            packages: self.metadata.packages.len(),
+            ..WorkspaceSummary::default()
+        };
+        for name in self.workspace_members() {
+            summary.members += 1;
+            if self
+                .version(name)
+                .is_some_and(|version| version.is_prerelease())
+            {
+                summary.prerelease_members += 1;
+            }
+            let has_key = |key: &str| {
+                self.package(name)
+                    .is_some_and(|raw_package| raw_package.contains_key(key))
+            };
+            if !has_key("description") {
+                summary.missing_description += 1;
+            }
+            if !has_key("license") && !has_key("license-file") {
+                summary.missing_license += 1;
+            }
+        }
+        summary
+    }
+
+    /// Return `true` if the root manifest of the workspace has no `[package]` table, i.e.
+    /// the workspace root is not a package and has no version to bump.
+    pub fn is_virtual_workspace(&self) -> bool {
+        self.root_manifest()
+            .is_some_and(|doc| !doc.contains_key("package"))
+    }
+
+    /// Bump the patch version of all workspace members.
+    ///
+    /// Members without a `version` key are skipped. If the version of any member is
+    /// malformed, nothing is bumped and the offending members are reported in
+    /// `ManifestError::Multiple`.
+    ///
+    /// Return the names and the new versions of the bumped members.
+    pub fn bump_all_patch_versions(
+        &mut self,
+    ) -> Result<Vec<(String, semver::Version)>, ManifestError> {
+        let _scope = self.edit_scope();
+        self.bump_all(BumpLevel::Patch)
+    }
+
+    /// Bump the minor version of all workspace members.
+    /// See `bump_all_patch_versions` for how malformed versions are handled.
+    pub fn bump_all_minor_versions(
+        &mut self,
+    ) -> Result<Vec<(String, semver::Version)>, ManifestError> {
+        let _scope = self.edit_scope();
+        self.bump_all(BumpLevel::Minor)
+    }
+
+    /// Bump the major version of all workspace members.
+    /// See `bump_all_patch_versions` for how malformed versions are handled.
+    pub fn bump_all_major_versions(
+        &mut self,
+    ) -> Result<Vec<(String, semver::Version)>, ManifestError> {
+        let _scope = self.edit_scope();
+        self.bump_all(BumpLevel::Major)
+    }
+
+    /// Bump the versions of the workspace members as decided by `f`.
+    ///
+    /// `f` is called with the name and the current version of each member, and returns
+    /// the part of the version to bump, or `None` to leave the member as is.
+    /// Members without a `version` key, or with a version that cannot be parsed, are skipped.
+    /// A version inherited from the workspace is bumped at most once.
+    ///
+    /// Return the names and the new versions of the bumped members, including every member
+    /// that inherits a bumped workspace version.
+    pub fn bump_with<F>(&mut self, mut f: F) -> Vec<(String, semver::Version)>
+    where
+        F: FnMut(&str, &semver::Version) -> Option<BumpLevel>,
+    {
+        let _scope = self.edit_scope();
+        let names = self
+            .workspace_members()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let mut bumped_workspace_version = false;
+        let mut bumped = HashSet::new();
+        for name in &names {
+            let inherited = self
+                .member_id(name)
+                .is_some_and(|package_id| self.inherits_version(package_id));
+            if inherited && bumped_workspace_version {
+                continue;
+            }
+            let bump = match self.version(name).and_then(|version| f(name, &version)) {
+                Some(bump) => bump,
+                None => continue,
+            };
+            if self.bump_version_inner(name, bump).is_some() {
+                bumped.insert(name.as_str());
+                if inherited {
+                    bumped_workspace_version = true;
+                }
             }
         }
+
+        names
+            .iter()
+            .filter(|name| {
+                bumped.contains(name.as_str())
+                    || (bumped_workspace_version
+                        && self
+                            .member_id(name)
+                            .is_some_and(|package_id| self.inherits_version(package_id)))
+            })
+            .filter_map(|name| Some((name.clone(), self.version(name)?)))
+            .collect()
+    }
+
+    /// Bump the given part of the version of the package with the given name.
+    /// Return the new version.
+    pub fn bump_version(&mut self, name: &str, level: BumpLevel) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_version_inner(name, level)
+    }
+
+    /// Bump the given part of the version of the package with the given name, like
+    /// `bump_version`, but report why nothing was bumped instead of returning `None`.
+    ///
+    /// Return `ManifestError::PackageNotFound` if there is no workspace member with the given
+    /// name, and `ManifestError::InvalidValue` or `ManifestError::SemVer` if its version is
+    /// missing, cannot be parsed, or is not a pre-release for `BumpLevel::Prerelease`.
+    pub fn bump_version_strict(
+        &mut self,
+        name: &str,
+        level: BumpLevel,
+    ) -> Result<semver::Version, ManifestError> {
+        let _scope = self.edit_scope();
+        if let Some(e) = self.bump_error(name, level) {
+            return Err(e);
+        }
+        self.bump_version_inner(name, level)
+            .ok_or_else(|| ManifestError::PackageNotFound(name.to_owned()))
     }
 
     /// Bump the patch version of the package with the given name.
     /// Return the new version.
     pub fn bump_patch_version(&mut self, name: &str) -> Option<semver::Version> {
-        self.bump_version_inner(name, SemVer::Patch)
+        let _scope = self.edit_scope();
+        self.bump_version_inner(name, BumpLevel::Patch)
+    }
+
+    /// Bump the given part of the version of the package with the given name, as
+    /// configured by `options`. Return the new version.
+    pub fn bump_version_with(
+        &mut self,
+        name: &str,
+        level: BumpLevel,
+        options: BumpOptions,
+    ) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.edit_version(name, |ver| {
+            let build = ver.build.clone();
+            Self::increment_version(ver, level)?;
+            if options.preserve_build_metadata {
+                ver.build = build;
+            }
+            Some(())
+        })
+    }
+
+    /// Bump the patch version of the package with the given name and set its build
+    /// metadata to `build`, e.g. `1.2.3` becomes `1.2.4+sha.abc1234` for `sha.abc1234`.
+    /// Return the new version.
+    ///
+    /// Return `None` without bumping if `build` is not valid build metadata, i.e.
+    /// non-empty identifiers of ASCII alphanumerics and hyphens separated by dots.
+    pub fn bump_patch_with_build(&mut self, name: &str, build: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        let is_valid = build.split('.').all(|identifier| {
+            !identifier.is_empty()
+                && identifier
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+        if !is_valid {
+            return None;
+        }
+        let build = semver::Version::parse(&format!("0.0.0+{}", build))
+            .ok()?
+            .build;
+        self.edit_version(name, |ver| {
+            Self::increment_version(ver, BumpLevel::Patch)?;
+            ver.build = build;
+            Some(())
+        })
+    }
+
+    /// Bump the patch version of the package with the given name if `pred` returns `true`
+    /// for its current version. Return the new version, or `None` if it was not bumped.
+    pub fn bump_patch_version_if<F>(&mut self, name: &str, pred: F) -> Option<semver::Version>
+    where
+        F: Fn(&semver::Version) -> bool,
+    {
+        let _scope = self.edit_scope();
+        self.edit_version(name, |ver| {
+            if !pred(ver) {
+                return None;
+            }
+            Self::increment_version(ver, BumpLevel::Patch)
+        })
     }
 
     /// Bump the minor version of the package with the given name.
     /// Return the new version.
     pub fn bump_minor_version(&mut self, name: &str) -> Option<semver::Version> {
-        self.bump_version_inner(name, SemVer::Minor)
+        let _scope = self.edit_scope();
+        self.bump_version_inner(name, BumpLevel::Minor)
     }
 
     /// Bump the minor version of the package with the given name.
     /// Return the new version.
     pub fn bump_major_version(&mut self, name: &str) -> Option<semver::Version> {
-        self.bump_version_inner(name, SemVer::Major)
+        let _scope = self.edit_scope();
+        self.bump_version_inner(name, BumpLevel::Major)
     }
 
-    fn bump_version_inner(&mut self, name: &str, bump: SemVer) -> Option<semver::Version> {
-        self.package_mut(name)
-            .and_then(|raw_package| raw_package.get_mut("version"))
-            .and_then(|raw_version| Self::bump_raw_version(raw_version, bump))
+    /// Bump the version of the package with the given name to the next version that
+    /// Cargo considers incompatible with the current one. Return the new version.
+    ///
+    /// That is the major version for `>=1.0.0` (`1.2.3` becomes `2.0.0`) and the minor
+    /// version for `0.y.z` (`0.3.1` becomes `0.4.0`). For `0.0.z` every release is
+    /// breaking, so the patch version is bumped (`0.0.3` becomes `0.0.4`).
+    pub fn bump_breaking(&mut self, name: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        let version = self.version(name)?;
+        let bump = if version.major > 0 {
+            BumpLevel::Major
+        } else if version.minor > 0 {
+            BumpLevel::Minor
+        } else {
+            BumpLevel::Patch
+        };
+        self.bump_version_inner(name, bump)
     }
 
-    fn package_mut(&mut self, name: &str) -> Option<&mut toml::value::Table> {
-        // `clone` to work around the borrow checker :(
-        let package_id = self.package_id(name)?.clone();
+    /// Bump the pre-release identifier of the package with the given name,
+    /// e.g. `1.2.0-beta.1` becomes `1.2.0-beta.2`.
+    /// Return the new version.
+    ///
+    /// Only the trailing numeric identifier is incremented (`1.0.0-alpha.2.3` becomes
+    /// `1.0.0-alpha.2.4`). If the pre-release does not end with a numeric identifier,
+    /// `.1` is appended (`1.0.0-rc` becomes `1.0.0-rc.1`). Return `None` if the
+    /// version has no pre-release.
+    pub fn bump_prerelease(&mut self, name: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_version_inner(name, BumpLevel::Prerelease)
+    }
 
-        self.raw_toml_map
-            .get_mut(&package_id)?
-            .as_table_mut()?
-            .get_mut("package")?
-            .as_table_mut()
+    /// Bump the major version of the package with the given name and attach
+    /// `label.1` as its pre-release, e.g. `1.2.3` becomes `2.0.0-rc.1`.
+    /// Return the new version.
+    pub fn bump_major_prerelease(&mut self, name: &str, label: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_prerelease_inner(name, BumpLevel::Major, label)
     }
 
-    /// Return a package id of the package with the given name.
-    fn package_id(&self, name: &str) -> Option<&cargo_metadata::PackageId> {
-        self.metadata
-            .packages
+    /// Bump the minor version of the package with the given name and attach
+    /// `label.1` as its pre-release, e.g. `1.2.3` becomes `1.3.0-rc.1`.
+    /// Return the new version.
+    pub fn bump_minor_prerelease(&mut self, name: &str, label: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_prerelease_inner(name, BumpLevel::Minor, label)
+    }
+
+    /// Bump the patch version of the package with the given name and attach
+    /// `label.1` as its pre-release, e.g. `1.2.3` becomes `1.2.4-rc.1`.
+    /// Return the new version.
+    pub fn bump_patch_prerelease(&mut self, name: &str, label: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_prerelease_inner(name, BumpLevel::Patch, label)
+    }
+
+    /// Bump the patch version of the package with the given name, and update the
+    /// requirements of the other workspace members that depend on it via `path`.
+    /// Return the new version.
+    ///
+    /// A requirement is rewritten to the new version only if it matched the old
+    /// version but no longer matches the new one. The operator and the number of
+    /// components are kept, e.g. `~0.3` becomes `~0.4` and `=0.3.0` becomes `=0.3.1`.
+    /// A `path` dependency in `[workspace.dependencies]` is rewritten the same way, which
+    /// updates every member that declares `dep.workspace = true`.
+    ///
+    /// If the package inherits `version.workspace = true`, the requirements on every member
+    /// inheriting the workspace version are updated, since they are all bumped together.
+    pub fn bump_patch_version_with_dependents(&mut self, name: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_with_dependents_inner(name, BumpLevel::Patch)
+    }
+
+    /// Bump the minor version of the package with the given name, and update the
+    /// requirements of the other workspace members that depend on it via `path`.
+    /// Return the new version.
+    pub fn bump_minor_version_with_dependents(&mut self, name: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_with_dependents_inner(name, BumpLevel::Minor)
+    }
+
+    /// Bump the major version of the package with the given name, and update the
+    /// requirements of the other workspace members that depend on it via `path`.
+    /// Return the new version.
+    pub fn bump_major_version_with_dependents(&mut self, name: &str) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_with_dependents_inner(name, BumpLevel::Major)
+    }
+
+    /// Set the requirement of the dependency `dep` in `[dependencies]` of `package` to `req`.
+    ///
+    /// Both `dep = "1.0"` and `dep = { version = "1.0", ... }` are supported.
+    /// Return `None` if either the package or the dependency does not exist, or if the
+    /// dependency is inherited with `dep.workspace = true`, which cannot have a version;
+    /// see `set_workspace_dependency` for those.
+    pub fn set_dependency_version(&mut self, package: &str, dep: &str, req: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        let raw_dep = self
+            .manifest_mut(package)?
+            .get_mut("dependencies")?
+            .as_table_like_mut()?
+            .get_mut(dep)
+            .filter(|raw_dep| !is_inherited(raw_dep))?;
+        let old_req = dependency::display_requirement(raw_dep);
+        if dependency::set_requirement(raw_dep, req) {
+            self.mark_dirty(package);
+            self.record_change(Change::Dependency {
+                package: package.to_owned(),
+                target: None,
+                kind: DependencyKind::Normal,
+                name: dep.to_owned(),
+                from: Some(old_req),
+                to: Some(req.to_owned()),
+            });
+        }
+        Some(())
+    }
+
+    /// Make the dependency `dep_key` in `[dependencies]` of `package` refer to the crate
+    /// `crate_name`, e.g. `serde2 = { package = "serde", version = "1" }`, so that a crate can
+    /// be depended on under another name. A dependency in the short form `dep = "1"` is
+    /// converted into an inline table. If `crate_name` is `dep_key`, `package` is removed.
+    /// Return `None` if either the package or the dependency does not exist.
+    pub fn rename_dependency(
+        &mut self,
+        package: &str,
+        dep_key: &str,
+        crate_name: &str,
+    ) -> Option<()> {
+        let _scope = self.edit_scope();
+        let raw_dep = self
+            .manifest_mut(package)?
+            .get_mut("dependencies")?
+            .as_table_like_mut()?
+            .get_mut(dep_key)?;
+        if dependency::crate_name(dep_key, raw_dep) == crate_name {
+            return Some(());
+        }
+        if let Some(req) = raw_dep.as_value().filter(|req| req.is_str()) {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("package", crate_name.into());
+            let mut req = req.clone();
+            let decor = std::mem::take(req.decor_mut());
+            table.insert("version", req);
+            *table.decor_mut() = decor;
+            *raw_dep = toml_edit::value(table);
+        } else if crate_name == dep_key {
+            match raw_dep.as_inline_table_mut() {
+                Some(table) => {
+                    dependency::remove_inline(table, "package");
+                }
+                None => {
+                    raw_dep.as_table_like_mut()?.remove("package");
+                }
+            }
+        } else if let Some(raw_package) = raw_dep.as_table_like_mut()?.get_mut("package") {
+            set_string(raw_package, crate_name.to_owned());
+        } else if let Some(table) = raw_dep.as_inline_table_mut() {
+            dependency::push_inline(table, "package", crate_name.into());
+        } else {
+            raw_dep
+                .as_table_like_mut()?
+                .insert("package", toml_edit::value(crate_name));
+        }
+        self.mark_dirty(package);
+        self.record_edit(package, format!("dependencies.{}", dep_key));
+        Some(())
+    }
+
+    /// Copy the requirement of each dependency in `[dependencies]` of `package` onto the
+    /// entry for the same crate in `[dev-dependencies]`, so that both tables agree.
+    /// Entries without a version and entries inherited with `dep.workspace = true` are left
+    /// as is. Return the number of changed entries, or `None` if the package does not exist.
+    pub fn sync_dev_dependency_versions(&mut self, package: &str) -> Option<usize> {
+        let _scope = self.edit_scope();
+        let raw_map = self.manifest_mut(package)?;
+        let reqs = raw_map
+            .get("dependencies")
+            .and_then(|deps| deps.as_table_like())
+            .map(|deps| {
+                deps.iter()
+                    .filter(|(_, dep)| !is_inherited(dep))
+                    .filter_map(|(key, dep)| {
+                        let req = dependency::requirement(dep)?;
+                        Some((dependency::crate_name(key, dep).to_owned(), req.to_owned()))
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+        let dev_deps = match raw_map
+            .get_mut(DependencyKind::Development.table_name())
+            .and_then(|deps| deps.as_table_like_mut())
+        {
+            Some(dev_deps) => dev_deps,
+            None => return Some(0),
+        };
+
+        let mut changes = Vec::new();
+        for (key, dep) in dev_deps.iter_mut() {
+            let crate_name = dependency::crate_name(key.get(), dep);
+            let req = match reqs.get(crate_name) {
+                Some(req) if !is_inherited(dep) && dependency::requirement(dep).is_some() => req,
+                _ => continue,
+            };
+            let old_req = dependency::display_requirement(dep);
+            if dependency::set_requirement(dep, req) {
+                changes.push(Change::Dependency {
+                    package: package.to_owned(),
+                    target: None,
+                    kind: DependencyKind::Development,
+                    name: key.get().to_owned(),
+                    from: Some(old_req),
+                    to: Some(req.clone()),
+                });
+            }
+        }
+
+        let count = changes.len();
+        if count > 0 {
+            self.mark_dirty(package);
+        }
+        for change in changes {
+            self.record_change(change);
+        }
+        Some(count)
+    }
+
+    /// Return the `(package, dependency)` pairs where a publishable workspace member has a
+    /// `path` dependency on another member without a version, which `cargo publish` rejects.
+    ///
+    /// `[dev-dependencies]` are not reported, as `cargo publish` drops a `path` dev-dependency
+    /// without a version.
+    pub fn missing_publish_versions(&self) -> Vec<(String, String)> {
+        let mut missing = Vec::new();
+        for name in self.workspace_members() {
+            if !self.is_publishable(name) {
+                continue;
+            }
+            let raw_map = match self.manifest(name) {
+                Some(raw_map) => raw_map,
+                None => continue,
+            };
+            for (_, kind, deps) in dependency::dependency_tables(raw_map) {
+                let deps = match deps.as_table_like() {
+                    Some(deps) => deps,
+                    None => continue,
+                };
+                for (key, dep) in deps.iter() {
+                    if lacks_publish_version(kind, dep)
+                        && self.contains_package(dependency::crate_name(key, dep))
+                    {
+                        missing.push((name.to_owned(), key.to_owned()));
+                    }
+                }
+            }
+        }
+        missing
+    }
+
+    /// Set the version of every dependency reported by `missing_publish_versions` to the
+    /// current version of the member it refers to, e.g. `version = "0.3.1"`.
+    /// Return the number of changed entries.
+    pub fn fill_publish_versions(&mut self) -> usize {
+        let _scope = self.edit_scope();
+        let versions = self
+            .versions()
+            .map(|(name, version)| (name.to_owned(), version.to_string()))
+            .collect::<HashMap<_, _>>();
+        let packages = self
+            .workspace_members()
+            .filter(|name| self.is_publishable(name))
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        let mut count = 0;
+        for package in &packages {
+            let raw_map = match self.manifest_mut(package) {
+                Some(raw_map) => raw_map,
+                None => continue,
+            };
+            let mut changes = Vec::new();
+            dependency::for_each_dependency_mut(raw_map, |target, kind, key, dep| {
+                if !lacks_publish_version(kind, dep) {
+                    return;
+                }
+                let version = match versions.get(dependency::crate_name(key, dep)) {
+                    Some(version) => version,
+                    None => return,
+                };
+                if dependency::set_requirement(dep, version) {
+                    changes.push(Change::Dependency {
+                        package: package.clone(),
+                        target: target.map(String::from),
+                        kind,
+                        name: key.to_owned(),
+                        from: Some("*".to_owned()),
+                        to: Some(version.clone()),
+                    });
+                }
+            });
+            if !changes.is_empty() {
+                self.mark_dirty(package);
+            }
+            count += changes.len();
+            for change in changes {
+                self.record_change(change);
+            }
+        }
+        count
+    }
+
+    /// Set the requirement on the crate `dep` to `req` in every dependency table of every
+    /// workspace member, including `[dev-dependencies]`, `[build-dependencies]` and the
+    /// tables under `[target]`. Return the number of manifests that were changed.
+    ///
+    /// Dependencies inherited with `dep.workspace = true` are left as is; use
+    /// `set_workspace_dependency` for them.
+    pub fn set_dependency_version_everywhere(&mut self, dep: &str, req: &str) -> usize {
+        let _scope = self.edit_scope();
+        let mut changes = Vec::new();
+        let mut changed_members = Vec::new();
+        for member in &self.metadata.workspace_members {
+            self.save_manifest(member);
+            let raw_map = match self.raw_toml_map.get_mut(member) {
+                Some(raw_map) => raw_map,
+                None => continue,
+            };
+            let member_name = match self.metadata.packages.iter().find(|p| p.id == *member) {
+                Some(p) => &p.name,
+                None => continue,
+            };
+
+            let mut changed = false;
+            dependency::for_each_dependency_mut(raw_map, |target, kind, key, raw_dep| {
+                if dependency::crate_name(key, raw_dep) != dep || is_inherited(raw_dep) {
+                    return;
+                }
+                let old_req = dependency::display_requirement(raw_dep);
+                if dependency::set_requirement(raw_dep, req) {
+                    changes.push(Change::Dependency {
+                        package: member_name.clone(),
+                        target: target.map(String::from),
+                        kind,
+                        name: key.to_owned(),
+                        from: Some(old_req),
+                        to: Some(req.to_owned()),
+                    });
+                    changed = true;
+                }
+            });
+            if changed {
+                changed_members.push(member.clone());
+            }
+        }
+
+        let count = changed_members.len();
+        for member in changed_members {
+            self.mark_dirty_by_id(member);
+        }
+        for change in changes {
+            self.record_change(change);
+        }
+        count
+    }
+
+    /// Add `dep_name = "req"` to `[dependencies]` of `package`, creating the table if needed.
+    /// An existing entry with the same name is replaced.
+    /// Return `None` if the package does not exist.
+    pub fn add_dependency(&mut self, package: &str, dep_name: &str, req: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        let dep = DependencyBuilder::new().version(req);
+        self.add_dependency_with(package, DependencyKind::Normal, dep_name, &dep)
+            .ok()
+    }
+
+    /// Add `dep_name = "req"` to `[dev-dependencies]` of `package`, creating the table if needed.
+    /// An existing entry with the same name is replaced.
+    /// Return `None` if the package does not exist.
+    pub fn add_dev_dependency(&mut self, package: &str, dep_name: &str, req: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        let dep = DependencyBuilder::new().version(req);
+        self.add_dependency_with(package, DependencyKind::Development, dep_name, &dep)
+            .ok()
+    }
+
+    /// Add `dep_name = "req"` to `[build-dependencies]` of `package`, creating the table if needed.
+    /// An existing entry with the same name is replaced.
+    /// Return `None` if the package does not exist.
+    pub fn add_build_dependency(&mut self, package: &str, dep_name: &str, req: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        let dep = DependencyBuilder::new().version(req);
+        self.add_dependency_with(package, DependencyKind::Build, dep_name, &dep)
+            .ok()
+    }
+
+    /// Add the dependency described by `dep` to the table of the given kind in `package`,
+    /// creating the table if needed. An existing entry with the same name is replaced.
+    ///
+    /// Return an error if the package does not exist or `dep` is invalid, see
+    /// `set_dependency`.
+    pub fn add_dependency_with(
+        &mut self,
+        package: &str,
+        kind: DependencyKind,
+        dep_name: &str,
+        dep: &DependencyBuilder,
+    ) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        dep.validate()?;
+        let mut raw_dep = dep.to_item();
+        let too_wide = self.style.is_too_wide(&raw_dep);
+        let deps = self
+            .manifest_mut(package)
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))?
+            .entry(kind.table_name())
+            .or_insert_with(toml_edit::table);
+        if too_wide && deps.is_table() {
+            if let Some(table) = raw_dep.as_inline_table() {
+                raw_dep = toml_edit::Item::Table(table.clone().into_table());
+            }
+        }
+        let old_dep = deps
+            .as_table_like_mut()
+            .ok_or_else(|| ManifestError::InvalidValue {
+                key: kind.table_name().to_owned(),
+                value: String::new(),
+                reason: "is not a table".to_owned(),
+            })?
+            .insert(dep_name, raw_dep);
+        self.mark_dirty(package);
+        self.record_change(Change::Dependency {
+            package: package.to_owned(),
+            target: None,
+            kind,
+            name: dep_name.to_owned(),
+            from: old_dep.as_ref().map(dependency::display_requirement),
+            to: Some(dependency::display_requirement(&dep.to_item())),
+        });
+        Ok(())
+    }
+
+    /// Call `f` with the top-level table of the manifest of `package` to make edits the other
+    /// methods do not cover. The manifest is written by the next `dump` if `f` changed it.
+    /// `pending_changes` reports each changed top-level key as a `Change::Other`.
+    /// Return `None` if the package does not exist.
+    pub fn edit_raw<F: FnMut(&mut toml_edit::Table)>(
+        &mut self,
+        package: &str,
+        mut f: F,
+    ) -> Option<()> {
+        let _scope = self.edit_scope();
+        let manifest = self.manifest_mut(package)?;
+        let before = manifest.clone();
+        f(manifest.as_table_mut());
+        if manifest.to_string() == before.to_string() {
+            return Some(());
+        }
+        let mut keys = manifest
             .iter()
-            .find(|p| p.name == name)
-            .map(|p| &p.id)
+            .map(|(key, _)| key.to_owned())
+            .collect::<Vec<_>>();
+        keys.extend(
+            before
+                .iter()
+                .map(|(key, _)| key.to_owned())
+                .filter(|key| !manifest.contains_key(key)),
+        );
+        let changed = keys
+            .into_iter()
+            .filter(|key| {
+                let item = |doc: &toml_edit::DocumentMut| doc.get(key).map(ToString::to_string);
+                item(manifest) != item(&before)
+            })
+            .collect::<Vec<_>>();
+        self.mark_dirty(package);
+        for key in changed {
+            self.record_edit(package, key);
+        }
+        Some(())
     }
 
-    // #[package]
-    // version = "0.1"
-    fn get_version_mut(table: &mut toml::Value) -> Option<&mut toml::Value> {
-        table
-            .as_table_mut()?
-            .get_mut("package")?
-            .as_table_mut()?
-            .get_mut("version")
+    /// Set `name` in `[dependencies]` of `package` to the entry described by `dep`,
+    /// replacing an existing entry with the same name.
+    ///
+    /// Return an error if `dep` has neither a version nor a `git` or `path` source, names
+    /// more than one source, or uses `branch`, `tag` or `rev` without `git`, or if the
+    /// package does not exist.
+    pub fn set_dependency(
+        &mut self,
+        package: &str,
+        name: &str,
+        dep: DependencyBuilder,
+    ) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        self.add_dependency_with(package, DependencyKind::Normal, name, &dep)
     }
 
-    fn bump_raw_version(version: &mut toml::Value, bump: SemVer) -> Option<semver::Version> {
-        let mut ver = semver::Version::parse(version.as_str()?).ok()?;
-        match bump {
-            SemVer::Major => ver.increment_major(),
-            SemVer::Minor => ver.increment_minor(),
-            SemVer::Patch => ver.increment_patch(),
+    /// Add `dep_name = { version = "req", optional = true }` to `[dependencies]` of `package`.
+    ///
+    /// If `feature_name` is given, `feature_name = ["dep:dep_name"]` is also added to
+    /// `[features]`. The `dep:` syntax keeps Cargo from exposing the dependency as an
+    /// implicit feature of the same name.
+    /// Return `None` if the package does not exist.
+    pub fn add_optional_dependency(
+        &mut self,
+        package: &str,
+        dep_name: &str,
+        req: &str,
+        feature_name: Option<&str>,
+    ) -> Option<()> {
+        let _scope = self.edit_scope();
+        let dep = DependencyBuilder::new().version(req).optional(true);
+        self.add_dependency_with(package, DependencyKind::Normal, dep_name, &dep)
+            .ok()?;
+        if let Some(feature_name) = feature_name {
+            self.add_feature(package, feature_name, &[format!("dep:{}", dep_name)])?;
         }
-        *version = toml::Value::String(ver.to_string());
-        Some(ver)
+        Some(())
     }
 
-    /// Write back the updated Cargo.toml.
-    pub fn dump(&mut self) -> io::Result<()> {
-        for p in &self.metadata.packages {
-            let mut f = fs::File::create(&p.manifest_path)?;
-            let raw_data = self.raw_toml_map.get(&p.id).unwrap();
-            f.write_all(
-                toml::to_string(raw_data)
-                    .map_err(io_error_other)?
-                    .as_bytes(),
-            )?;
+    /// Remove `dep_name` from `[dependencies]` of `package`.
+    /// Return `true` if the dependency was removed.
+    pub fn remove_dependency(&mut self, package: &str, dep_name: &str) -> bool {
+        let _scope = self.edit_scope();
+        self.remove_dependency_with(package, DependencyKind::Normal, dep_name, false)
+    }
+
+    /// Remove `dep_name` from `[dev-dependencies]` of `package`.
+    /// Return `true` if the dependency was removed.
+    pub fn remove_dev_dependency(&mut self, package: &str, dep_name: &str) -> bool {
+        let _scope = self.edit_scope();
+        self.remove_dependency_with(package, DependencyKind::Development, dep_name, false)
+    }
+
+    /// Remove `dep_name` from `[build-dependencies]` of `package`.
+    /// Return `true` if the dependency was removed.
+    pub fn remove_build_dependency(&mut self, package: &str, dep_name: &str) -> bool {
+        let _scope = self.edit_scope();
+        self.remove_dependency_with(package, DependencyKind::Build, dep_name, false)
+    }
+
+    /// Remove `dep_name` from the table of the given kind in `package`.
+    /// If `drop_empty_table` is `true` and no dependency is left, the table itself is removed.
+    /// Return `true` if the dependency was removed.
+    pub fn remove_dependency_with(
+        &mut self,
+        package: &str,
+        kind: DependencyKind,
+        dep_name: &str,
+        drop_empty_table: bool,
+    ) -> bool {
+        let _scope = self.edit_scope();
+        let raw_map = match self.manifest_mut(package) {
+            Some(raw_map) => raw_map,
+            None => return false,
+        };
+        let deps = match raw_map
+            .get_mut(kind.table_name())
+            .and_then(|deps| deps.as_table_like_mut())
+        {
+            Some(deps) => deps,
+            None => return false,
+        };
+        let old_dep = match deps.remove(dep_name) {
+            Some(old_dep) => old_dep,
+            None => return false,
+        };
+        if drop_empty_table && deps.is_empty() {
+            raw_map.remove(kind.table_name());
+        }
+        self.mark_dirty(package);
+        self.record_change(Change::Dependency {
+            package: package.to_owned(),
+            target: None,
+            kind,
+            name: dep_name.to_owned(),
+            from: Some(dependency::display_requirement(&old_dep)),
+            to: None,
+        });
+        true
+    }
+
+    /// Remove `path` from the entries in `[dev-dependencies]` of `package` that also have a
+    /// version, including the platform-specific ones, so that the registry version is used.
+    /// Return `None` if the package does not exist.
+    pub fn strip_dev_dependency_paths(&mut self, package: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.strip_dependency_paths(package, |kind| kind == DependencyKind::Development)
+    }
+
+    /// Prepare `package` for `cargo publish` by removing `path` from every dependency entry
+    /// that also has a version, in all dependency tables, leaving version-only requirements.
+    /// Entries with a `path` but no version are left as is.
+    /// Return `None` if the package does not exist.
+    pub fn prepare_for_publish(&mut self, package: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.strip_dependency_paths(package, |_| true)
+    }
+
+    fn strip_dependency_paths<F>(&mut self, package: &str, filter: F) -> Option<()>
+    where
+        F: Fn(DependencyKind) -> bool,
+    {
+        let mut stripped = Vec::new();
+        dependency::for_each_dependency_mut(
+            self.manifest_mut(package)?,
+            |target, kind, key, dep| {
+                if filter(kind) && dependency::strip_path(dep) {
+                    stripped.push(format!("{}.{}", dependency::table_path(target, kind), key));
+                }
+            },
+        );
+        if !stripped.is_empty() {
+            self.mark_dirty(package);
+        }
+        for key in stripped {
+            self.record_edit(package, key);
+        }
+        Some(())
+    }
+
+    /// Return the dependencies in `[dependencies]` of `package` paired with their
+    /// version requirements, as written in the manifest.
+    /// Return `None` if the package does not exist.
+    pub fn dependencies(&self, package: &str) -> Option<Vec<(String, String)>> {
+        self.dependencies_of_kind(package, DependencyKind::Normal)
+    }
+
+    /// Return the dependencies in the table of the given kind in `package` paired with
+    /// their version requirements, as written in the manifest.
+    ///
+    /// A dependency without a version requirement (e.g. a `git` or `path` dependency)
+    /// is reported with `*`, which is what Cargo assumes for it.
+    /// Return `None` if the package does not exist.
+    pub fn dependencies_of_kind(
+        &self,
+        package: &str,
+        kind: DependencyKind,
+    ) -> Option<Vec<(String, String)>> {
+        let raw_map = self.manifest(package)?;
+        let deps = match raw_map
+            .get(kind.table_name())
+            .and_then(|deps| deps.as_table_like())
+        {
+            Some(deps) => deps,
+            None => return Some(Vec::new()),
+        };
+        Some(
+            deps.iter()
+                .map(|(name, dep)| {
+                    let req = dependency::requirement(dep).unwrap_or("*");
+                    (name.to_owned(), req.to_owned())
+                })
+                .collect(),
+        )
+    }
+
+    /// Sort the entries of `[dependencies]`, `[dev-dependencies]` and `[build-dependencies]`
+    /// of `package` alphabetically, each table on its own. Comments stay with their entries.
+    ///
+    /// Dependencies written as separate tables, e.g. `[dependencies.foo]`, keep their place.
+    /// Return `None` if the package does not exist.
+    pub fn sort_dependencies(&mut self, package: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        let mut sorted = Vec::new();
+        for (target, kind, deps) in dependency::dependency_tables_mut(self.manifest_mut(package)?) {
+            let deps = match deps.as_table_like_mut() {
+                Some(deps) => deps,
+                None => continue,
+            };
+            // Only the entries written as values move; `[dependencies.foo]` tables stay put.
+            let value_keys = |deps: &dyn toml_edit::TableLike| {
+                deps.iter()
+                    .filter(|(_, dep)| dep.is_value())
+                    .map(|(key, _)| key.to_owned())
+                    .collect::<Vec<_>>()
+            };
+            let before = value_keys(deps);
+            if before.windows(2).any(|pair| pair[0] > pair[1]) {
+                deps.sort_values();
+                if value_keys(deps) != before {
+                    sorted.push(dependency::table_path(target.as_deref(), kind));
+                }
+            }
+        }
+        if !sorted.is_empty() {
+            self.mark_dirty(package);
+        }
+        for key in sorted {
+            self.record_edit(package, key);
+        }
+        Some(())
+    }
+
+    /// Sort the dependency tables of all workspace members like `sort_dependencies`.
+    pub fn sort_all_dependencies(&mut self) {
+        let _scope = self.edit_scope();
+        let names = self
+            .workspace_members()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        for name in &names {
+            self.sort_dependencies(name);
+        }
+    }
+
+    /// Write every dependency of `package` that has more than a version requirement in
+    /// the given style, e.g. turn `[dependencies.foo]` tables into inline tables.
+    /// Dependencies in the short form `foo = "1.0"` are left as is.
+    /// Return `None` if the package does not exist.
+    pub fn set_dependency_style(&mut self, package: &str, style: DepStyle) -> Option<()> {
+        let _scope = self.edit_scope();
+        let raw_map = self.manifest_mut(package)?;
+        let mut restyled = Vec::new();
+        for (target, kind, deps) in dependency::dependency_tables_mut(raw_map) {
+            if let Some(deps) = deps.as_table_mut() {
+                if dependency::set_style(deps, style) {
+                    restyled.push(dependency::table_path(target.as_deref(), kind));
+                }
+            }
+        }
+        if !restyled.is_empty() {
+            self.mark_dirty(package);
+        }
+        for key in restyled {
+            self.record_edit(package, key);
+        }
+        Some(())
+    }
+
+    /// Set `edition` in `[package]` of `package`, e.g. `2021`.
+    /// Return the previous edition, if any.
+    ///
+    /// Return an error if the package does not exist or `edition` is not one of
+    /// `2015`, `2018`, `2021` and `2024`.
+    pub fn set_edition(
+        &mut self,
+        package: &str,
+        edition: &str,
+    ) -> Result<Option<String>, ManifestError> {
+        let _scope = self.edit_scope();
+        if !EDITIONS.contains(&edition) {
+            return Err(ManifestError::InvalidValue {
+                key: "edition".to_owned(),
+                value: edition.to_owned(),
+                reason: format!("expected one of {}", EDITIONS.join(", ")),
+            });
+        }
+        self.set_package_str(package, "edition", edition)
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))
+    }
+
+    /// Set `description` in `[package]` of `package`.
+    /// Return `None` if the package does not exist.
+    pub fn set_description(&mut self, package: &str, value: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_str(package, "description", value)
+            .map(|_| ())
+    }
+
+    /// Set `license` in `[package]` of `package` to an SPDX expression, e.g. `MIT OR Apache-2.0`,
+    /// removing `license-file`.
+    /// Return `None` if the package does not exist.
+    pub fn set_license(&mut self, package: &str, value: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_str(package, "license", value)?;
+        self.remove_package_key(package, "license-file")
+    }
+
+    /// Set `license-file` in `[package]` of `package` to the path of a file holding the
+    /// license, relative to the manifest, removing `license`.
+    /// Return `None` if the package does not exist.
+    pub fn set_license_file(&mut self, package: &str, path: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_str(package, "license-file", path)?;
+        self.remove_package_key(package, "license")
+    }
+
+    /// Return how the license of `package` is declared. If both `license` and
+    /// `license-file` are present, `license` is returned, as crates.io ignores the file then.
+    pub fn license(&self, package: &str) -> Option<License> {
+        let raw_package = self.package(package)?;
+        if let Some(license) = raw_package.get("license").and_then(|l| l.as_str()) {
+            return Some(License::Expression(license.to_owned()));
+        }
+        let license_file = raw_package.get("license-file")?.as_str()?;
+        Some(License::File(license_file.to_owned()))
+    }
+
+    /// Set `repository` in `[package]` of `package`.
+    /// Return `None` if the package does not exist.
+    pub fn set_repository(&mut self, package: &str, value: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_str(package, "repository", value)
+            .map(|_| ())
+    }
+
+    /// Set `homepage` in `[package]` of `package`.
+    /// Return `None` if the package does not exist.
+    pub fn set_homepage(&mut self, package: &str, value: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_str(package, "homepage", value).map(|_| ())
+    }
+
+    /// Set `documentation` in `[package]` of `package`.
+    /// Return `None` if the package does not exist.
+    pub fn set_documentation(&mut self, package: &str, value: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_str(package, "documentation", value)
+            .map(|_| ())
+    }
+
+    /// Return `links` in `[package]` of `package`.
+    pub fn links(&self, package: &str) -> Option<&str> {
+        self.package(package)?.get("links")?.as_str()
+    }
+
+    /// Set `links` in `[package]` of `package`, the name of the native library the package
+    /// links to. `None` removes the key.
+    ///
+    /// Return an error if the package does not exist, `value` is empty, or another workspace
+    /// member already links to the same library, which Cargo rejects.
+    pub fn set_links(&mut self, package: &str, value: Option<&str>) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        let value = match value {
+            Some(value) => value,
+            None => {
+                return self
+                    .remove_package_key(package, "links")
+                    .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()));
+            }
+        };
+
+        let invalid = |reason: String| ManifestError::InvalidValue {
+            key: "links".to_owned(),
+            value: value.to_owned(),
+            reason,
+        };
+        if value.is_empty() {
+            return Err(invalid("must not be empty".to_owned()));
+        }
+        let other = self
+            .workspace_members()
+            .find(|&name| name != package && self.links(name) == Some(value));
+        if let Some(other) = other {
+            return Err(invalid(format!("already used by `{}`", other)));
         }
+        self.set_package_str(package, "links", value)
+            .map(|_| ())
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))
+    }
+
+    /// Set `default-run` in `[package]` of `package`, the binary `cargo run` runs when there
+    /// are several. `None` removes the key.
+    ///
+    /// Return an error if the package does not exist or has no binary named `bin`, either
+    /// found by `cargo metadata` or declared in `[[bin]]`.
+    pub fn set_default_run(
+        &mut self,
+        package: &str,
+        bin: Option<&str>,
+    ) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        let not_found = || ManifestError::PackageNotFound(package.to_owned());
+        let bin = match bin {
+            Some(bin) => bin,
+            None => {
+                return self
+                    .remove_package_key(package, "default-run")
+                    .ok_or_else(not_found)
+            }
+        };
+
+        let p = self
+            .package_metadata(package)
+            .filter(|_| self.contains_package(package))
+            .ok_or_else(not_found)?;
+        let mut bins = p
+            .targets
+            .iter()
+            .filter(|target| target.kind.iter().any(|kind| kind == "bin"))
+            .map(|target| target.name.as_str())
+            .collect::<Vec<_>>();
+        if let Some(declared) = self
+            .manifest(package)
+            .and_then(|raw_map| raw_map.get("bin"))
+            .and_then(|bins| bins.as_array_of_tables())
+        {
+            bins.extend(declared.iter().filter_map(|b| b.get("name")?.as_str()));
+        }
+        if !bins.contains(&bin) {
+            return Err(ManifestError::InvalidValue {
+                key: "default-run".to_owned(),
+                value: bin.to_owned(),
+                reason: format!("`{}` has no such binary", package),
+            });
+        }
+        self.set_package_str(package, "default-run", bin)
+            .map(|_| ())
+            .ok_or_else(not_found)
+    }
 
+    /// Set `rust-version` in `[package]` of `package`, e.g. `1.70`.
+    ///
+    /// Return an error if the package does not exist or `version` is not of the form
+    /// `major.minor` or `major.minor.patch`; Cargo accepts neither pre-release nor build
+    /// metadata in `rust-version`.
+    pub fn set_rust_version(&mut self, package: &str, version: &str) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        validate_rust_version(version)?;
+        self.set_package_str(package, "rust-version", version)
+            .map(|_| ())
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))
+    }
+
+    /// Return `edition` and `rust-version` of `package`, following values inherited from
+    /// the workspace. A `rust-version` like `1.70` is read as `1.70.0`.
+    ///
+    /// Return `None` if the package does not exist; a missing or malformed key is
+    /// reported as a `None` field instead.
+    pub fn toolchain_requirements(&self, package: &str) -> Option<ToolchainReq> {
+        let package_id = self.package_id(package)?;
+        self.raw_package(package_id)?;
+        let edition = self
+            .package_item(package_id, "edition")
+            .and_then(|edition| edition.as_str())
+            .map(String::from);
+        let rust_version = self
+            .package_item(package_id, "rust-version")
+            .and_then(|rust_version| rust_version.as_str())
+            .and_then(|rust_version| {
+                let rust_version = match rust_version.matches('.').count() {
+                    1 => format!("{}.0", rust_version),
+                    _ => rust_version.to_owned(),
+                };
+                semver::Version::parse(&rust_version).ok()
+            });
+        Some(ToolchainReq {
+            edition,
+            rust_version,
+        })
+    }
+
+    /// Set `rust-version` in `[package]` of all workspace members.
+    /// Return an error if `version` is invalid, see `set_rust_version`.
+    pub fn set_all_rust_versions(&mut self, version: &str) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        validate_rust_version(version)?;
+        let names = self
+            .workspace_members()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        for name in &names {
+            self.set_package_str(name, "rust-version", version);
+        }
         Ok(())
     }
-}
 
-fn io_error_other<E>(error: E) -> io::Error
-where
-    E: Into<Box<dyn std::error::Error + Send + Sync>>,
-{
-    io::Error::new(io::ErrorKind::Other, error.into())
+    /// Rename the workspace member `old` to `new`, rewriting `[package] name` and every
+    /// dependency on it in the workspace, including `[workspace.dependencies]`.
+    ///
+    /// Dependencies declared as `old = { path = ... }` are renamed to `new = { path = ... }`,
+    /// and `alias = { package = "old", ... }` becomes `alias = { package = "new", ... }`.
+    /// The entries of `[features]` that refer to a renamed key, e.g. `dep:old`, `old/std`
+    /// and `old?/std`, are rewritten to match.
+    /// Return an error if `old` does not exist or `new` is already used by a workspace member.
+    pub fn rename_package(&mut self, old: &str, new: &str) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        if self.member_id(new).is_some() {
+            return Err(ManifestError::InvalidValue {
+                key: "name".to_owned(),
+                value: new.to_owned(),
+                reason: "already used by another workspace member".to_owned(),
+            });
+        }
+        let package_id = self
+            .member_id(old)
+            .cloned()
+            .ok_or_else(|| ManifestError::PackageNotFound(old.to_owned()))?;
+        if let Some(raw_name) = self
+            .package_mut(old)
+            .and_then(|raw_package| raw_package.get_mut("name"))
+        {
+            set_string(raw_name, new.to_owned());
+            self.mark_dirty_by_id(package_id.clone());
+            self.record_change(Change::PackageField {
+                package: new.to_owned(),
+                key: "name".to_owned(),
+                from: Some(old.to_owned()),
+                to: Some(new.to_owned()),
+            });
+        }
+
+        // Keep looking the package up by its name.
+        self.save_name(&package_id);
+        for p in &mut self.metadata.packages {
+            if p.id == package_id {
+                p.name = new.to_owned();
+            }
+        }
+
+        let mut edits = Vec::new();
+        for member in &self.metadata.workspace_members {
+            self.save_manifest(member);
+            let raw_map = match self.raw_toml_map.get_mut(member) {
+                Some(raw_map) => raw_map,
+                None => continue,
+            };
+            let mut keys = Vec::new();
+            let mut renamed_key = false;
+            for (target, kind, deps) in dependency::dependency_tables_mut(raw_map) {
+                let deps = match deps.as_table_like_mut() {
+                    Some(deps) => deps,
+                    None => continue,
+                };
+                let changed = dependency::rename_package_fields(deps, old, new);
+                let renamed = dependency::rename_dependency_key(deps, old, new);
+                if changed || renamed {
+                    keys.push(dependency::table_path(target.as_deref(), kind));
+                }
+                renamed_key |= renamed;
+            }
+            if renamed_key && features::rename_dependency(raw_map, old, new) {
+                keys.push("features".to_owned());
+            }
+            if keys.is_empty() {
+                continue;
+            }
+            let name = self
+                .metadata
+                .packages
+                .iter()
+                .find(|p| p.id == *member)
+                .map(|p| p.name.clone());
+            if let Some(name) = name {
+                edits.push((member.clone(), name, keys));
+            }
+        }
+        for (member, name, keys) in edits {
+            self.mark_dirty_by_id(member);
+            for key in keys {
+                self.record_edit(&name, key);
+            }
+        }
+        if let Some(deps) = self.workspace_dependencies_mut() {
+            let changed = dependency::rename_package_fields(deps, old, new);
+            if dependency::rename_dependency_key(deps, old, new) || changed {
+                self.mark_root_dirty();
+                self.record_root_edit("workspace.dependencies".to_owned());
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `key` in `[package]` of `package` to an arbitrary value, e.g. `rust-version`,
+    /// `publish` or `keywords`. Return the previous value, if any.
+    ///
+    /// The value is written as is: callers are responsible for passing a value of the
+    /// TOML type Cargo expects for `key`. Return `None` without writing anything if the
+    /// package does not exist.
+    pub fn set_package_field(
+        &mut self,
+        package: &str,
+        key: &str,
+        value: toml_edit::Value,
+    ) -> Option<toml_edit::Item> {
+        let _scope = self.edit_scope();
+        self.set_package_value(package, key, value).flatten()
+    }
+
+    /// Set `publish = true` or `publish = false` in `[package]` of `package`.
+    /// Return `None` if the package does not exist.
+    pub fn set_publish(&mut self, package: &str, publish: bool) -> Option<()> {
+        let _scope = self.edit_scope();
+        self.set_package_value(package, "publish", publish.into())
+            .map(|_| ())
+    }
+
+    /// Set `publish = [...registries]` in `[package]` of `package`, restricting the
+    /// registries the package may be published to.
+    /// Return `None` if the package does not exist.
+    pub fn set_publish_registries(&mut self, package: &str, registries: &[String]) -> Option<()> {
+        let _scope = self.edit_scope();
+        let mut registries = registries.iter().collect::<toml_edit::Array>();
+        self.style.format_array(&mut registries);
+        self.set_package_value(package, "publish", registries.into())
+            .map(|_| ())
+    }
+
+    /// Return the patterns in `include` in `[package]` of `package`, which select the files
+    /// of the published package.
+    pub fn include(&self, package: &str) -> Option<Vec<String>> {
+        string_array(self.package(package)?.get("include"))
+    }
+
+    /// Return the patterns in `exclude` in `[package]` of `package`, which remove files from
+    /// the published package.
+    pub fn exclude(&self, package: &str) -> Option<Vec<String>> {
+        string_array(self.package(package)?.get("exclude"))
+    }
+
+    /// Overwrite `include` in `[package]` of `package`. An empty slice removes the key.
+    ///
+    /// Return an error if the package does not exist or has `exclude`, which Cargo ignores
+    /// when `include` is given.
+    pub fn set_include(&mut self, package: &str, patterns: &[String]) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        self.set_file_patterns(package, "include", "exclude", patterns)
+    }
+
+    /// Overwrite `exclude` in `[package]` of `package`. An empty slice removes the key.
+    ///
+    /// Return an error if the package does not exist or has `include`, in which case Cargo
+    /// ignores `exclude`.
+    pub fn set_exclude(&mut self, package: &str, patterns: &[String]) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        self.set_file_patterns(package, "exclude", "include", patterns)
+    }
+
+    fn set_file_patterns(
+        &mut self,
+        package: &str,
+        key: &str,
+        other_key: &str,
+        patterns: &[String],
+    ) -> Result<(), ManifestError> {
+        let mut array = patterns.iter().collect::<toml_edit::Array>();
+        self.style.format_array(&mut array);
+        let raw_package = self
+            .package_mut(package)
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))?;
+        let old_value = raw_package.get(key).map(change::display_item);
+        if patterns.is_empty() {
+            if raw_package.remove(key).is_none() {
+                return Ok(());
+            }
+        } else {
+            if raw_package.contains_key(other_key) {
+                return Err(ManifestError::InvalidValue {
+                    key: key.to_owned(),
+                    value: patterns.join(", "),
+                    reason: format!("cannot be combined with `{}`", other_key),
+                });
+            }
+            if string_array(raw_package.get(key)).as_deref() == Some(patterns) {
+                return Ok(());
+            }
+            raw_package.insert(key, toml_edit::value(array));
+        }
+        self.mark_package_field_dirty(package, key, old_value);
+        Ok(())
+    }
+
+    /// Return `true` if `package` may be published to some registry, i.e. `publish` is
+    /// missing, `true` or a non-empty list of registries.
+    pub fn is_publishable(&self, package: &str) -> bool {
+        let raw_package = match self.package(package) {
+            Some(raw_package) => raw_package,
+            None => return false,
+        };
+        match raw_package.get("publish") {
+            None => true,
+            Some(publish) => match publish.as_value() {
+                Some(toml_edit::Value::Boolean(publish)) => *publish.value(),
+                Some(toml_edit::Value::Array(registries)) => !registries.is_empty(),
+                _ => true,
+            },
+        }
+    }
+
+    /// Overwrite `authors` in `[package]` of `package`.
+    /// An empty slice removes the key.
+    /// Return `None` if the package does not exist.
+    pub fn set_authors(&mut self, package: &str, authors: &[String]) -> Option<()> {
+        let _scope = self.edit_scope();
+        let mut raw_authors = authors.iter().collect::<toml_edit::Array>();
+        self.style.format_array(&mut raw_authors);
+        let raw_package = self.package_mut(package)?;
+        let old_authors = raw_package.get("authors").map(change::display_item);
+        if authors.is_empty() {
+            if raw_package.remove("authors").is_none() {
+                return Some(());
+            }
+        } else {
+            if string_array(raw_package.get("authors")).as_deref() == Some(authors) {
+                return Some(());
+            }
+            raw_package.insert("authors", toml_edit::value(raw_authors));
+        }
+        self.mark_package_field_dirty(package, "authors", old_authors);
+        Some(())
+    }
+
+    /// Append `author` to `authors` in `[package]` of `package`, unless it is already listed.
+    ///
+    /// Return an error if the package does not exist, or if `authors` is inherited with
+    /// `authors.workspace = true` or is not an array.
+    pub fn add_author(&mut self, package: &str, author: &str) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        let raw_package = self
+            .package_mut(package)
+            .ok_or_else(|| ManifestError::PackageNotFound(package.to_owned()))?;
+        let old_authors = raw_package.get("authors").map(change::display_item);
+        let raw_authors = raw_package
+            .entry("authors")
+            .or_insert_with(|| toml_edit::value(toml_edit::Array::new()));
+        let reason = if is_inherited(raw_authors) {
+            "is inherited from `[workspace.package]`"
+        } else {
+            "is not an array"
+        };
+        let authors = raw_authors
+            .as_array_mut()
+            .ok_or_else(|| ManifestError::InvalidValue {
+                key: "authors".to_owned(),
+                value: old_authors.clone().unwrap_or_default(),
+                reason: reason.to_owned(),
+            })?;
+        if authors.iter().all(|a| a.as_str() != Some(author)) {
+            authors.push(author);
+            self.mark_package_field_dirty(package, "authors", old_authors);
+        }
+        Ok(())
+    }
+
+    /// Remove the `[badges]` table of `package`, which crates.io no longer displays.
+    /// Return `true` if the table was removed.
+    pub fn remove_badges(&mut self, package: &str) -> bool {
+        let _scope = self.edit_scope();
+        let removed = self
+            .manifest_mut(package)
+            .and_then(|manifest| manifest.remove("badges"))
+            .is_some();
+        if removed {
+            self.mark_dirty(package);
+            self.record_edit(package, "badges".to_owned());
+        }
+        removed
+    }
+
+    /// Set `[badges.<service>]` of `package` to `attrs`, e.g. `repository = "foo/bar"` for
+    /// the `maintenance` service, creating `[badges]` if needed.
+    /// Return `None` if the package does not exist.
+    pub fn set_badge(
+        &mut self,
+        package: &str,
+        service: &str,
+        attrs: toml_edit::Table,
+    ) -> Option<()> {
+        let _scope = self.edit_scope();
+        let badges = self
+            .manifest_mut(package)?
+            .entry("badges")
+            .or_insert_with(|| {
+                let mut badges = toml_edit::Table::new();
+                badges.set_implicit(true);
+                toml_edit::Item::Table(badges)
+            })
+            .as_table_mut()?;
+        badges.insert(service, toml_edit::Item::Table(attrs));
+        self.mark_dirty(package);
+        self.record_edit(package, format!("badges.{}", service));
+        Some(())
+    }
+
+    /// Return the names and the current versions of the workspace members.
+    /// Members whose version is missing or cannot be parsed are skipped.
+    pub fn versions(&self) -> impl Iterator<Item = (&str, semver::Version)> {
+        self.workspace_members()
+            .filter_map(move |name| Some((name, self.version(name)?)))
+    }
+
+    /// Return the greatest version among the workspace members, e.g. to pick the next
+    /// common version of a workspace whose members are released together.
+    /// Members without a version or with a malformed one are ignored.
+    pub fn max_version(&self) -> Option<semver::Version> {
+        self.versions().map(|(_, version)| version).max()
+    }
+
+    /// Set the version of every workspace member to `version`.
+    /// Members without a version or with a malformed one are skipped.
+    pub fn set_all_versions(&mut self, version: semver::Version) {
+        let _scope = self.edit_scope();
+        let members = self.metadata.workspace_members.clone();
+        for package_id in &members {
+            if self.version_by_id(package_id).as_ref() == Some(&version) {
+                continue;
+            }
+            self.edit_version_by_id(package_id, |ver| {
+                *ver = version.clone();
+                Some(())
+            });
+        }
+    }
+
+    /// Bump the given part of the greatest version among the workspace members and set
+    /// every member to the result, so that all members share one version.
+    ///
+    /// Unlike `bump_all_patch_versions` and friends, members are not bumped from their own
+    /// versions: e.g. with `foo 1.2.0` and `bar 1.3.1`, a minor bump sets both to `1.4.0`.
+    /// Members behind the others jump straight to the shared version, and the member that is
+    /// furthest ahead decides it, so no member ends up with a lower version than before.
+    /// Members without a version or with a malformed one are skipped.
+    ///
+    /// Return the new version, or `None` if no member has a version or `level` is
+    /// `Prerelease` and the greatest version is not a pre-release.
+    pub fn bump_all_lockstep(&mut self, level: BumpLevel) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        let mut version = self.max_version()?;
+        Self::increment_version(&mut version, level)?;
+        self.set_all_versions(version.clone());
+        Some(version)
+    }
+
+    /// Return `name` in `[package]` of the package with the given name exactly as written in
+    /// its manifest, e.g. to compare it with the names used in path dependencies.
+    pub fn raw_package_name(&self, name: &str) -> Option<String> {
+        let raw_name = self.package(name)?.get("name")?;
+        raw_name.as_str().map(String::from)
+    }
+
+    /// Return the path to the `Cargo.toml` of the package with the given name.
+    pub fn manifest_path(&self, name: &str) -> Option<&Path> {
+        self.package_metadata(name)
+            .map(|p| p.manifest_path.as_path())
+    }
+
+    /// Return the current version of the package with the given name.
+    ///
+    /// If the package inherits its version with `version.workspace = true`, the version
+    /// in `[workspace.package]` of the root manifest is returned.
+    pub fn version(&self, name: &str) -> Option<semver::Version> {
+        self.version_by_id(self.package_id(name)?)
+    }
+
+    /// Return the current version of the package with the given id, like `version`.
+    ///
+    /// Unlike names, ids are unique even if several packages share a name.
+    pub fn version_by_id(&self, id: &cargo_metadata::PackageId) -> Option<semver::Version> {
+        let raw_version = self.package_item(id, "version")?;
+        semver::Version::parse(raw_version.as_str()?).ok()
+    }
+
+    /// Set the version of the package with the given name.
+    /// Return the previous version.
+    pub fn set_version(&mut self, name: &str, version: semver::Version) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        let package_id = self.member_id(name)?.clone();
+        let raw_version = self.version_item_mut(&package_id)?;
+        let old_version = semver::Version::parse(raw_version.as_str()?).ok()?;
+        if old_version != version {
+            set_string(raw_version, version.to_string());
+            self.mark_version_dirty(&package_id, &old_version, &version);
+        }
+        Some(old_version)
+    }
+
+    /// Bump the given part of the version of the workspace member with the given id.
+    /// Return the new version.
+    pub fn bump_version_by_id(
+        &mut self,
+        id: &cargo_metadata::PackageId,
+        level: BumpLevel,
+    ) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.edit_version_by_id(id, |ver| Self::increment_version(ver, level))
+    }
+
+    /// Bump the patch version of the workspace member with the given id.
+    /// Return the new version.
+    pub fn bump_patch_version_by_id(
+        &mut self,
+        id: &cargo_metadata::PackageId,
+    ) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_version_by_id(id, BumpLevel::Patch)
+    }
+
+    /// Bump the minor version of the workspace member with the given id.
+    /// Return the new version.
+    pub fn bump_minor_version_by_id(
+        &mut self,
+        id: &cargo_metadata::PackageId,
+    ) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_version_by_id(id, BumpLevel::Minor)
+    }
+
+    /// Bump the major version of the workspace member with the given id.
+    /// Return the new version.
+    pub fn bump_major_version_by_id(
+        &mut self,
+        id: &cargo_metadata::PackageId,
+    ) -> Option<semver::Version> {
+        let _scope = self.edit_scope();
+        self.bump_version_by_id(id, BumpLevel::Major)
+    }
+
+    fn bump_version_inner(&mut self, name: &str, bump: BumpLevel) -> Option<semver::Version> {
+        self.edit_version(name, |ver| Self::increment_version(ver, bump))
+    }
+
+    fn bump_all(
+        &mut self,
+        bump: BumpLevel,
+    ) -> Result<Vec<(String, semver::Version)>, ManifestError> {
+        self.check_versions()?;
+        Ok(self.bump_with(|_, _| Some(bump)))
+    }
+
+    /// Check that the version of every workspace member that has one can be parsed.
+    fn check_versions(&self) -> Result<(), ManifestError> {
+        let errors = self
+            .workspace_members()
+            .filter_map(|name| Some((name.to_owned(), self.version_error(name)?)))
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ManifestError::Multiple(errors))
+        }
+    }
+
+    /// Return why `level` of the version of the package with the given name cannot be
+    /// bumped, or `None` if it can be.
+    fn bump_error(&self, name: &str, level: BumpLevel) -> Option<ManifestError> {
+        if self.member_id(name).is_none() {
+            return Some(ManifestError::PackageNotFound(name.to_owned()));
+        }
+        if let Some(e) = self.version_error(name) {
+            return Some(e);
+        }
+        let (value, reason) = match self.version(name) {
+            None => (String::new(), "is missing"),
+            Some(version) if level == BumpLevel::Prerelease && !version.is_prerelease() => {
+                (version.to_string(), "is not a pre-release")
+            }
+            Some(_) => return None,
+        };
+        Some(ManifestError::InvalidValue {
+            key: "version".to_owned(),
+            value,
+            reason: reason.to_owned(),
+        })
+    }
+
+    /// Return why the version of the workspace member with the given name cannot be parsed,
+    /// or `None` if it can be parsed or is missing.
+    fn version_error(&self, name: &str) -> Option<ManifestError> {
+        let raw_version = self.package_item(self.member_id(name)?, "version")?;
+        match raw_version.as_str() {
+            Some(version) => Some(semver::Version::parse(version).err()?.into()),
+            None => Some(ManifestError::InvalidValue {
+                key: "version".to_owned(),
+                value: raw_version.to_string().trim().to_owned(),
+                reason: "expected a string".to_owned(),
+            }),
+        }
+    }
+
+    fn bump_prerelease_inner(
+        &mut self,
+        name: &str,
+        bump: BumpLevel,
+        label: &str,
+    ) -> Option<semver::Version> {
+        // Let semver split and validate the label, e.g. `rc` -> `[rc, 1]`.
+        let pre = semver::Version::parse(&format!("0.0.0-{}.1", label))
+            .ok()?
+            .pre;
+
+        self.edit_version(name, |ver| {
+            let build = ver.build.clone();
+            Self::increment_version(ver, bump)?;
+            ver.pre = pre;
+            ver.build = build;
+            Some(())
+        })
+    }
+
+    fn bump_with_dependents_inner(
+        &mut self,
+        name: &str,
+        bump: BumpLevel,
+    ) -> Option<semver::Version> {
+        let old_version = self.version(name)?;
+        let package_id = self.member_id(name)?.clone();
+        // Bumping `[workspace.package] version` bumps every member inheriting it.
+        let bumped = if self.inherits_version(&package_id) {
+            self.metadata
+                .packages
+                .iter()
+                .filter(|p| self.is_member(&p.id) && self.inherits_version(&p.id))
+                .map(|p| p.name.clone())
+                .collect()
+        } else {
+            vec![name.to_owned()]
+        };
+        let new_version = self.bump_version_inner(name, bump)?;
+        for name in &bumped {
+            self.update_dependents(name, &old_version, &new_version);
+        }
+        Some(new_version)
+    }
+
+    /// Rewrite the requirements on the package with the given name in the other
+    /// workspace members from `old_version` to `new_version`.
+    fn update_dependents(
+        &mut self,
+        name: &str,
+        old_version: &semver::Version,
+        new_version: &semver::Version,
+    ) {
+        let package_id = match self.member_id(name) {
+            Some(package_id) => package_id.clone(),
+            None => return,
+        };
+
+        let mut changes = Vec::new();
+        for member in &self.metadata.workspace_members {
+            if *member == package_id {
+                continue;
+            }
+            self.save_manifest(member);
+            let raw_map = match self.raw_toml_map.get_mut(member) {
+                Some(raw_map) => raw_map,
+                None => continue,
+            };
+            let member_name = match self.metadata.packages.iter().find(|p| p.id == *member) {
+                Some(p) => &p.name,
+                None => continue,
+            };
+
+            let mut changed = false;
+            dependency::for_each_dependency_mut(raw_map, |target, kind, key, dep| {
+                if let Some((old_req, new_req)) =
+                    dependency::update_path_requirement(key, dep, name, old_version, new_version)
+                {
+                    changes.push(Change::Dependency {
+                        package: member_name.clone(),
+                        target: target.map(String::from),
+                        kind,
+                        name: key.to_owned(),
+                        from: Some(old_req),
+                        to: Some(new_req),
+                    });
+                    changed = true;
+                }
+            });
+            if changed {
+                self.dirty.insert(member.clone());
+                self.bump_revision();
+            }
+        }
+
+        // Members declaring `dep.workspace = true` inherit the requirement from here.
+        if let Some(deps) = self.workspace_dependencies_mut() {
+            let mut changed = false;
+            for (key, dep) in deps.iter_mut() {
+                if let Some((old_req, new_req)) =
+                    dependency::update_path_requirement(&key, dep, name, old_version, new_version)
+                {
+                    changes.push(Change::WorkspaceDependency {
+                        name: key.get().to_owned(),
+                        from: old_req,
+                        to: new_req,
+                    });
+                    changed = true;
+                }
+            }
+            if changed {
+                self.mark_root_dirty();
+            }
+        }
+        for change in changes {
+            self.record_change(change);
+        }
+    }
+
+    /// Apply `f` to the version of the package with the given name.
+    /// Return the new version, or `None` if the version is missing or `f` fails.
+    fn edit_version<F>(&mut self, name: &str, f: F) -> Option<semver::Version>
+    where
+        F: FnOnce(&mut semver::Version) -> Option<()>,
+    {
+        let package_id = self.member_id(name)?.clone();
+        self.edit_version_by_id(&package_id, f)
+    }
+
+    /// Apply `f` to the version of the workspace member with the given id.
+    /// Return the new version, or `None` if the version is missing or `f` fails.
+    fn edit_version_by_id<F>(
+        &mut self,
+        package_id: &cargo_metadata::PackageId,
+        f: F,
+    ) -> Option<semver::Version>
+    where
+        F: FnOnce(&mut semver::Version) -> Option<()>,
+    {
+        let old_version = self.version_by_id(package_id)?;
+        let new_version = self
+            .version_item_mut(package_id)
+            .and_then(|raw_version| Self::edit_raw_version(raw_version, f))?;
+        self.mark_version_dirty(package_id, &old_version, &new_version);
+        Some(new_version)
+    }
+
+    /// Return `true` if the package with the given id declares `version.workspace = true`.
+    fn inherits_version(&self, package_id: &cargo_metadata::PackageId) -> bool {
+        self.raw_package(package_id)
+            .and_then(|raw_package| raw_package.get("version"))
+            .is_some_and(is_inherited)
+    }
+
+    /// Return `key` in `[package]` of the package with the given id, following
+    /// `key.workspace = true` to `[workspace.package]` of the root manifest.
+    fn package_item(
+        &self,
+        package_id: &cargo_metadata::PackageId,
+        key: &str,
+    ) -> Option<&toml_edit::Item> {
+        let raw_value = self.raw_package(package_id)?.get(key)?;
+        if is_inherited(raw_value) {
+            self.workspace_package()?.get(key)
+        } else {
+            Some(raw_value)
+        }
+    }
+
+    /// Return the `version` of the workspace member with the given id, following
+    /// `version.workspace = true` to `[workspace.package]` of the root manifest.
+    fn version_item_mut(
+        &mut self,
+        package_id: &cargo_metadata::PackageId,
+    ) -> Option<&mut toml_edit::Item> {
+        if is_inherited(self.raw_package_mut(package_id)?.get("version")?) {
+            self.workspace_package_mut()?.get_mut("version")
+        } else {
+            self.raw_package_mut(package_id)?.get_mut("version")
+        }
+    }
+
+    fn mark_version_dirty(
+        &mut self,
+        package_id: &cargo_metadata::PackageId,
+        old_version: &semver::Version,
+        new_version: &semver::Version,
+    ) {
+        if self.inherits_version(package_id) {
+            self.mark_root_dirty();
+        } else {
+            self.mark_dirty_by_id(package_id.clone());
+        }
+        let name = self
+            .metadata
+            .packages
+            .iter()
+            .find(|p| p.id == *package_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+        self.record_change(Change::Version {
+            package: name,
+            from: old_version.clone(),
+            to: new_version.clone(),
+        });
+    }
+
+    /// Set `key = "value"` in `[package]` of `package`.
+    /// Return the previous string value, or `None` if the package does not exist.
+    fn set_package_str(&mut self, package: &str, key: &str, value: &str) -> Option<Option<String>> {
+        let raw_package = self.package_mut(package)?;
+        let old_value = raw_package
+            .get(key)
+            .and_then(|old_value| old_value.as_str())
+            .map(String::from);
+        if old_value.as_deref() != Some(value) {
+            let old_display = raw_package.get(key).map(change::display_item);
+            match raw_package.get_mut(key) {
+                Some(raw_value) => set_string(raw_value, value.to_owned()),
+                None => {
+                    raw_package.insert(key, toml_edit::value(value));
+                }
+            }
+            self.mark_package_field_dirty(package, key, old_display);
+        }
+        Some(old_value)
+    }
+
+    /// Remove `key` from `[package]` of `package`.
+    /// Return `None` if the package does not exist.
+    fn remove_package_key(&mut self, package: &str, key: &str) -> Option<()> {
+        if let Some(old_value) = self.package_mut(package)?.remove(key) {
+            let old_value = change::display_item(&old_value);
+            self.mark_package_field_dirty(package, key, Some(old_value));
+        }
+        Some(())
+    }
+
+    /// Set `key` in `[package]` of `package` to `value`, unless it already has that value.
+    /// Return the previous value, or `None` if the package does not exist.
+    fn set_package_value(
+        &mut self,
+        package: &str,
+        key: &str,
+        value: toml_edit::Value,
+    ) -> Option<Option<toml_edit::Item>> {
+        let raw_package = self.package_mut(package)?;
+        let old_value = raw_package.get(key).cloned();
+        let unchanged = old_value
+            .as_ref()
+            .and_then(|old_value| old_value.as_value())
+            .is_some_and(|old_value| same_value(old_value, &value));
+        if unchanged {
+            return Some(old_value);
+        }
+        match raw_package.get_mut(key) {
+            Some(raw_value) => set_value(raw_value, value),
+            None => {
+                raw_package.insert(key, toml_edit::Item::Value(value));
+            }
+        }
+        let old_display = old_value.as_ref().map(change::display_item);
+        self.mark_package_field_dirty(package, key, old_display);
+        Some(old_value)
+    }
+
+    /// Remember that `key` in `[package]` of `package` was changed from `old_value`.
+    fn mark_package_field_dirty(&mut self, package: &str, key: &str, old_value: Option<String>) {
+        let new_value = self
+            .package(package)
+            .and_then(|raw_package| raw_package.get(key))
+            .map(change::display_item);
+        self.mark_dirty(package);
+        self.record_change(Change::PackageField {
+            package: package.to_owned(),
+            key: key.to_owned(),
+            from: old_value,
+            to: new_value,
+        });
+    }
+
+    fn package(&self, name: &str) -> Option<&toml_edit::Table> {
+        self.manifest(name)?.get("package")?.as_table()
+    }
+
+    fn raw_package(&self, package_id: &cargo_metadata::PackageId) -> Option<&toml_edit::Table> {
+        self.manifest_by_id(package_id)?.get("package")?.as_table()
+    }
+
+    /// Return `[package]` of the workspace member with the given id.
+    fn raw_package_mut(
+        &mut self,
+        package_id: &cargo_metadata::PackageId,
+    ) -> Option<&mut toml_edit::Table> {
+        if !self.is_member(package_id) {
+            return None;
+        }
+        self.save_manifest(package_id);
+        self.raw_toml_map
+            .get_mut(package_id)?
+            .get_mut("package")?
+            .as_table_mut()
+    }
+
+    fn package_mut(&mut self, name: &str) -> Option<&mut toml_edit::Table> {
+        self.manifest_mut(name)?.get_mut("package")?.as_table_mut()
+    }
+
+    fn manifest(&self, name: &str) -> Option<&toml_edit::DocumentMut> {
+        self.manifest_by_id(self.package_id(name)?)
+    }
+
+    /// Return the manifest of the package with the given id, reading it first if the package
+    /// is not a workspace member.
+    fn manifest_by_id(
+        &self,
+        package_id: &cargo_metadata::PackageId,
+    ) -> Option<&toml_edit::DocumentMut> {
+        match self.raw_toml_map.get(package_id) {
+            Some(raw_data) => Some(raw_data),
+            None => self
+                .dependency_manifests
+                .get(package_id)?
+                .get()
+                .map(|(raw_data, _)| raw_data),
+        }
+    }
+
+    fn manifest_mut(&mut self, name: &str) -> Option<&mut toml_edit::DocumentMut> {
+        // Borrow `metadata` and `raw_toml_map` separately rather than `self` as a whole.
+        let package_id = find_member(&self.metadata, name)?;
+        self.save_manifest(package_id);
+        self.raw_toml_map.get_mut(package_id)
+    }
+
+    // [workspace.package]
+    // version = "0.1"
+    fn workspace_package(&self) -> Option<&toml_edit::Table> {
+        self.root_manifest()?
+            .get("workspace")?
+            .get("package")?
+            .as_table()
+    }
+
+    fn workspace_package_mut(&mut self) -> Option<&mut toml_edit::Table> {
+        self.root_manifest_mut()?
+            .get_mut("workspace")?
+            .as_table_mut()?
+            .get_mut("package")?
+            .as_table_mut()
+    }
+
+    fn root_manifest(&self) -> Option<&toml_edit::DocumentMut> {
+        match self.root_package_id() {
+            Some(package_id) => self.raw_toml_map.get(package_id),
+            None => self.virtual_manifest.as_ref(),
+        }
+    }
+
+    fn root_manifest_mut(&mut self) -> Option<&mut toml_edit::DocumentMut> {
+        match self.root_package_id().cloned() {
+            Some(package_id) => {
+                self.save_manifest(&package_id);
+                self.raw_toml_map.get_mut(&package_id)
+            }
+            None => {
+                self.save_virtual_manifest();
+                self.virtual_manifest.as_mut()
+            }
+        }
+    }
+
+    fn root_manifest_path(&self) -> PathBuf {
+        self.metadata.workspace_root.join(&self.file_name)
+    }
+
+    /// Return a package id of the package at the workspace root, or `None` for a virtual manifest.
+    fn root_package_id(&self) -> Option<&cargo_metadata::PackageId> {
+        let root_manifest_path = self.root_manifest_path();
+        self.metadata
+            .packages
+            .iter()
+            .find(|p| p.manifest_path == root_manifest_path)
+            .map(|p| &p.id)
+    }
+
+    /// Remember that the root manifest needs to be written back.
+    fn mark_root_dirty(&mut self) {
+        match self.root_package_id().cloned() {
+            Some(package_id) => self.mark_dirty_by_id(package_id),
+            None => {
+                self.virtual_manifest_dirty = true;
+                self.bump_revision();
+            }
+        }
+    }
+
+    /// Remember that the manifest of the package with the given name needs to be written back.
+    fn mark_dirty(&mut self, name: &str) {
+        if let Some(package_id) = self.member_id(name).cloned() {
+            self.mark_dirty_by_id(package_id);
+        }
+    }
+
+    /// Remember that the manifest of the package with the given id needs to be written back.
+    fn mark_dirty_by_id(&mut self, package_id: cargo_metadata::PackageId) {
+        self.dirty.insert(package_id);
+        self.bump_revision();
+    }
+
+    /// Return the `cargo metadata` output for the package with the given name.
+    fn package_metadata(&self, name: &str) -> Option<&cargo_metadata::Package> {
+        let package_id = self.package_id(name)?;
+        self.metadata.packages.iter().find(|p| p.id == *package_id)
+    }
+
+    /// Return a package id of the package with the given name.
+    /// Workspace members take precedence over other packages with the same name.
+    fn package_id(&self, name: &str) -> Option<&cargo_metadata::PackageId> {
+        self.member_id(name).or_else(|| {
+            self.metadata
+                .packages
+                .iter()
+                .find(|p| p.name == name)
+                .map(|p| &p.id)
+        })
+    }
+
+    /// Return a package id of the workspace member with the given name.
+    fn member_id(&self, name: &str) -> Option<&cargo_metadata::PackageId> {
+        find_member(&self.metadata, name)
+    }
+
+    fn is_member(&self, package_id: &cargo_metadata::PackageId) -> bool {
+        self.metadata.workspace_members.contains(package_id)
+    }
+
+    fn edit_raw_version<F>(version: &mut toml_edit::Item, f: F) -> Option<semver::Version>
+    where
+        F: FnOnce(&mut semver::Version) -> Option<()>,
+    {
+        let mut ver = semver::Version::parse(version.as_str()?).ok()?;
+        f(&mut ver)?;
+        set_string(version, ver.to_string());
+        Some(ver)
+    }
+
+    fn increment_version(ver: &mut semver::Version, bump: BumpLevel) -> Option<()> {
+        match bump {
+            BumpLevel::Major => ver.increment_major(),
+            BumpLevel::Minor => ver.increment_minor(),
+            BumpLevel::Patch => ver.increment_patch(),
+            BumpLevel::Prerelease => match ver.pre.last_mut() {
+                None => return None,
+                Some(semver::Identifier::Numeric(n)) => *n += 1,
+                Some(semver::Identifier::AlphaNumeric(_)) => {
+                    ver.pre.push(semver::Identifier::Numeric(1))
+                }
+            },
+        }
+        Some(())
+    }
+
+    /// Run `f`, keeping its edits only if it returns `Ok`.
+    ///
+    /// If `f` returns `Err`, every edit it made is rolled back and the error is returned,
+    /// so a failed batch of edits leaves nothing to `dump` behind.
+    pub fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Metadata) -> Result<T, E>,
+    {
+        let _scope = self.edit_scope();
+        self.begin_savepoint();
+        let revision = self.revision();
+        let result = f(self);
+        let savepoint = self.end_savepoint();
+        if result.is_err() {
+            self.restore(savepoint);
+            self.reset_revision(revision);
+        }
+        result
+    }
+
+    /// Return the content `dump` would write, keyed by the manifest path.
+    /// Manifests that were not modified are not included.
+    pub fn render(&self) -> HashMap<PathBuf, String> {
+        self.render_dirty().into_iter().collect()
+    }
+
+    /// Write the manifest of the package with the given name into `writer`.
+    /// Return an error of kind `NotFound` if there is no such package.
+    pub fn write_package<W: Write>(&self, name: &str, writer: &mut W) -> io::Result<()> {
+        let not_found = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("package `{}` not found", name),
+            )
+        };
+        let p = self.package_metadata(name).ok_or_else(not_found)?;
+        let content = match self.dependency_manifests.get(&p.id) {
+            Some(lazy) => {
+                let (raw_data, format) = lazy.get().ok_or_else(not_found)?;
+                format.apply(raw_data.to_string())
+            }
+            None => {
+                let raw_data = self.raw_toml_map.get(&p.id).ok_or_else(not_found)?;
+                self.render_manifest(&p.manifest_path, raw_data)
+            }
+        };
+        writer.write_all(content.as_bytes())
+    }
+
+    /// Write back the updated Cargo.toml.
+    /// Manifests that were not modified are left untouched.
+    pub fn dump(&mut self) -> Result<(), ManifestError> {
+        self.dump_and_report().map(|_| ())
+    }
+
+    /// Write back the updated Cargo.toml like `dump`.
+    /// Return the paths of the manifests that were written.
+    pub fn dump_and_report(&mut self) -> Result<Vec<PathBuf>, ManifestError> {
+        self.dump_with_progress(|_| {})?;
+        Ok(self.written.clone())
+    }
+
+    /// Write back the updated Cargo.toml like `dump`, calling `on_write` with the path of
+    /// each manifest after it is written, e.g. to show progress.
+    /// `modified_manifest_count` tells how many manifests will be written.
+    pub fn dump_with_progress<F: FnMut(&Path)>(
+        &mut self,
+        mut on_write: F,
+    ) -> Result<(), ManifestError> {
+        let mut written = Vec::new();
+        for (path, content) in self.render_dirty() {
+            write_manifest(&path, &content)?;
+            on_write(&path);
+            written.push(path);
+        }
+        self.finish_dump(written);
+        Ok(())
+    }
+
+    /// Return the number of manifests the next `dump` writes.
+    pub fn modified_manifest_count(&self) -> usize {
+        self.dirty_manifests().len()
+    }
+
+    /// Forget the edits after the manifests at `written` were written.
+    fn finish_dump(&mut self, written: Vec<PathBuf>) {
+        self.dirty.clear();
+        self.virtual_manifest_dirty = false;
+        self.changes.clear();
+        self.written = written;
+        self.clear_history();
+    }
+
+    /// Write the manifests of all workspace members and the root manifest under `root`,
+    /// at the same paths relative to `root` as they have relative to the workspace root.
+    ///
+    /// Unlike `dump`, unmodified manifests are written too and the originals are left
+    /// untouched, so the manifests are still considered modified afterwards.
+    pub fn dump_to<P: AsRef<Path>>(&self, root: P) -> io::Result<()> {
+        let workspace_root = &self.metadata.workspace_root;
+        let mut manifests = self
+            .metadata
+            .packages
+            .iter()
+            .filter(|p| self.is_member(&p.id))
+            .filter_map(|p| Some((&p.manifest_path, self.raw_toml_map.get(&p.id)?)))
+            .collect::<Vec<_>>();
+        let root_manifest_path = self.root_manifest_path();
+        if let Some(raw_data) = self.virtual_manifest.as_ref() {
+            manifests.push((&root_manifest_path, raw_data));
+        }
+
+        for (path, raw_data) in manifests {
+            let relative_path = path.strip_prefix(workspace_root).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is outside the workspace root", path.display()),
+                )
+            })?;
+            let out_path = root.as_ref().join(relative_path);
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            write_manifest(&out_path, &self.render_manifest(path, raw_data))?;
+        }
+        Ok(())
+    }
+
+    /// Render the modified manifests, keeping the layout of the original files.
+    fn render_dirty(&self) -> Vec<(PathBuf, String)> {
+        self.dirty_manifests()
+            .into_iter()
+            .map(|(path, raw_data)| {
+                let content = self.render_manifest(&path, raw_data);
+                (path, content)
+            })
+            .collect()
+    }
+
+    /// Return the paths and the documents of the manifests `dump` writes.
+    fn dirty_manifests(&self) -> Vec<(PathBuf, &toml_edit::DocumentMut)> {
+        let mut manifests = self
+            .metadata
+            .packages
+            .iter()
+            .filter(|p| self.dirty.contains(&p.id))
+            .filter_map(|p| Some((p.manifest_path.clone(), self.raw_toml_map.get(&p.id)?)))
+            .collect::<Vec<_>>();
+        if let Some(raw_data) = self.virtual_manifest.as_ref() {
+            if self.virtual_manifest_dirty {
+                manifests.push((self.root_manifest_path(), raw_data));
+            }
+        }
+        manifests
+    }
+
+    fn render_manifest(&self, path: &Path, raw_data: &toml_edit::DocumentMut) -> String {
+        let content = raw_data.to_string();
+        match self.formats.get(path) {
+            Some(format) => format.apply(content),
+            None => content,
+        }
+    }
+}
+
+/// Return `true` if `dep` is a `path` dependency without a version that `cargo publish`
+/// rejects, i.e. one that is not in `[dev-dependencies]`.
+fn lacks_publish_version(kind: DependencyKind, dep: &toml_edit::Item) -> bool {
+    kind != DependencyKind::Development
+        && dependency::is_path_dependency(dep)
+        && dependency::requirement(dep).is_none()
+}
+
+/// Return a package id of the workspace member with the given name.
+fn find_member<'a>(
+    metadata: &'a cargo_metadata::Metadata,
+    name: &str,
+) -> Option<&'a cargo_metadata::PackageId> {
+    metadata
+        .packages
+        .iter()
+        .find(|p| p.name == name && metadata.workspace_members.contains(&p.id))
+        .map(|p| &p.id)
+}
+
+/// The manifest of a package that is read the first time it is looked at.
+struct LazyManifest {
+    path: PathBuf,
+    /// `None` if the manifest cannot be read or parsed.
+    manifest: OnceLock<Option<(toml_edit::DocumentMut, FileFormat)>>,
+}
+
+impl LazyManifest {
+    fn new(path: &Path) -> Self {
+        LazyManifest {
+            path: path.to_path_buf(),
+            manifest: OnceLock::new(),
+        }
+    }
+
+    fn get(&self) -> Option<&(toml_edit::DocumentMut, FileFormat)> {
+        self.manifest
+            .get_or_init(|| read_manifest(&self.path, LineEnding::Auto).ok())
+            .as_ref()
+    }
+}
+
+fn read_manifest(
+    path: &Path,
+    line_ending: LineEnding,
+) -> Result<(toml_edit::DocumentMut, FileFormat), ManifestError> {
+    let content = fs::read_to_string(path)?;
+    let raw_value = content
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|source| ManifestError::TomlParse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    Ok((raw_value, FileFormat::detect(&content, line_ending)))
+}
+
+/// Check that `version` is a valid `rust-version`, i.e. `major.minor` or `major.minor.patch`.
+fn validate_rust_version(version: &str) -> Result<(), ManifestError> {
+    let parts = version.split('.').collect::<Vec<_>>();
+    let is_valid = (2..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ManifestError::InvalidValue {
+            key: "rust-version".to_owned(),
+            value: version.to_owned(),
+            reason: "expected `major.minor` or `major.minor.patch`".to_owned(),
+        })
+    }
+}
+
+/// Write `content` to a sibling temporary file (e.g. `Cargo.toml.tmp`) and rename it
+/// over `path`, so that an interrupted write never leaves a truncated manifest behind.
+///
+/// If `path` is a symlink, the file it points to is replaced and the link is kept.
+/// The permissions of the existing file are carried over to the new one.
+fn write_manifest(path: &Path, content: &str) -> io::Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let permissions = fs::metadata(&path).ok().map(|m| m.permissions());
+    let mut tmp_file_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_file_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_file_name);
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut f| {
+            f.write_all(content.as_bytes())?;
+            if let Some(permissions) = permissions {
+                f.set_permissions(permissions)?;
+            }
+            f.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp_path, &path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Return `true` if the value is inherited from the workspace, i.e. `key.workspace = true`.
+pub(crate) fn is_inherited(item: &toml_edit::Item) -> bool {
+    item.get("workspace").and_then(|w| w.as_bool()) == Some(true)
+}
+
+/// Return the strings in the array stored in `item`, or `None` if it is not an array of strings.
+pub(crate) fn string_array(item: Option<&toml_edit::Item>) -> Option<Vec<String>> {
+    item?
+        .as_array()?
+        .iter()
+        .map(|v| v.as_str().map(String::from))
+        .collect()
+}
+
+/// Overwrite the string stored in `item`, keeping the surrounding whitespace and comments.
+pub(crate) fn set_string(item: &mut toml_edit::Item, value: String) {
+    set_value(item, toml_edit::Value::from(value));
+}
+
+/// Return `true` if `a` and `b` hold the same data, whatever their formatting.
+pub(crate) fn same_value(a: &toml_edit::Value, b: &toml_edit::Value) -> bool {
+    use toml_edit::Value;
+
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same_value(a, b)))
+        }
+        _ => false,
+    }
+}
+
+/// Overwrite the value stored in `item`, keeping the surrounding whitespace and comments.
+pub(crate) fn set_value(item: &mut toml_edit::Item, mut value: toml_edit::Value) {
+    if let Some(old_value) = item.as_value() {
+        *value.decor_mut() = old_value.decor().clone();
+    }
+    *item = toml_edit::Item::Value(value);
 }
@@ -0,0 +1,22 @@
+//! Writing of the manifests on a thread pool.
+
+use rayon::prelude::*;
+
+use crate::{write_manifest, ManifestError, Metadata};
+
+impl Metadata {
+    /// Write back the updated Cargo.toml like `dump`, rendering and writing the manifests
+    /// on the thread pool of `rayon`.
+    ///
+    /// If writing any manifest fails, the first error is returned and the edits are kept,
+    /// so that `dump` can be retried; some manifests may already have been written.
+    pub fn dump_parallel(&mut self) -> Result<(), ManifestError> {
+        let manifests = self.dirty_manifests();
+        manifests.par_iter().try_for_each(|(path, raw_data)| {
+            write_manifest(path, &self.render_manifest(path, raw_data))
+        })?;
+        let written = manifests.into_iter().map(|(path, _)| path).collect();
+        self.finish_dump(written);
+        Ok(())
+    }
+}
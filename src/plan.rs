@@ -0,0 +1,79 @@
+//! Bumping the versions of several workspace members at once, as listed in a plan.
+
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+use crate::{BumpLevel, ManifestError, Metadata};
+
+/// The part of the version to bump for each listed workspace member, by package name.
+///
+/// With the `serde` feature, a plan is deserialized from a map, e.g. from the TOML
+/// `foo = "minor"` or the JSON `{ "foo": "minor" }`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct BumpPlan {
+    levels: BTreeMap<String, BumpLevel>,
+}
+
+impl BumpPlan {
+    /// Create an empty plan.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plan to bump `level` of the version of `package`, replacing an earlier entry for it.
+    pub fn insert(&mut self, package: &str, level: BumpLevel) -> &mut Self {
+        self.levels.insert(package.to_owned(), level);
+        self
+    }
+
+    /// Return the part of the version to bump for `package`.
+    pub fn get(&self, package: &str) -> Option<BumpLevel> {
+        self.levels.get(package).copied()
+    }
+
+    /// Return the planned packages and bumps, ordered by package name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, BumpLevel)> {
+        self.levels
+            .iter()
+            .map(|(package, level)| (package.as_str(), *level))
+    }
+}
+
+impl FromIterator<(String, BumpLevel)> for BumpPlan {
+    fn from_iter<I: IntoIterator<Item = (String, BumpLevel)>>(iter: I) -> Self {
+        BumpPlan {
+            levels: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Metadata {
+    /// Bump the versions of the packages listed in `plan`, see `bump_with`.
+    ///
+    /// Every listed package is checked before anything is bumped, so either all of them are
+    /// bumped or none is. Return `ManifestError::Multiple` with an error for each package
+    /// that is not a workspace member, has no `version` or one that cannot be parsed, or is
+    /// planned a `Prerelease` bump without being a pre-release.
+    ///
+    /// A version inherited from the workspace is bumped once, by the level planned for the
+    /// first of its members in name order.
+    pub fn apply_bump_plan(
+        &mut self,
+        plan: &BumpPlan,
+    ) -> Result<Vec<(String, semver::Version)>, ManifestError> {
+        let _scope = self.edit_scope();
+        let errors = plan
+            .iter()
+            .filter_map(|(name, level)| Some((name.to_owned(), self.bump_error(name, level)?)))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(ManifestError::Multiple(errors));
+        }
+        Ok(self.bump_with(|name, _| plan.get(name)))
+    }
+}
@@ -0,0 +1,53 @@
+//! Editing of the `[profile]` tables of the root manifest.
+
+use crate::Metadata;
+
+impl Metadata {
+    /// Return `key` in `[profile.<profile>]` of the root manifest, e.g. `lto` in
+    /// `[profile.release]`.
+    pub fn profile_setting(&self, profile: &str, key: &str) -> Option<&toml_edit::Value> {
+        self.root_manifest()?
+            .get("profile")?
+            .get(profile)?
+            .get(key)?
+            .as_value()
+    }
+
+    /// Set `key` in `[profile.<profile>]` of the root manifest to `value`, e.g.
+    /// `codegen-units = 1` in `[profile.release]`, creating the tables if needed.
+    ///
+    /// Cargo ignores profiles outside the root manifest, so the manifests of the other
+    /// members are never edited.
+    /// Return `None` if the root manifest has no such table and it cannot be created, e.g.
+    /// because `profile` is not a table.
+    pub fn set_profile_setting(
+        &mut self,
+        profile: &str,
+        key: &str,
+        value: toml_edit::Value,
+    ) -> Option<()> {
+        let _scope = self.edit_scope();
+        let profiles = self
+            .root_manifest_mut()?
+            .entry("profile")
+            .or_insert_with(|| {
+                let mut profiles = toml_edit::Table::new();
+                profiles.set_implicit(true);
+                toml_edit::Item::Table(profiles)
+            })
+            .as_table_like_mut()?;
+        let settings = profiles
+            .entry(profile)
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()?;
+        match settings.get_mut(key) {
+            Some(raw_value) => crate::set_value(raw_value, value),
+            None => {
+                settings.insert(key, toml_edit::Item::Value(value));
+            }
+        }
+        self.mark_root_dirty();
+        self.record_root_edit(format!("profile.{}.{}", profile, key));
+        Some(())
+    }
+}
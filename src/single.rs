@@ -0,0 +1,150 @@
+//! Editing of a single manifest held in memory, without `cargo metadata` or the filesystem.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::dependency::{self, DependencyBuilder, DependencyKind};
+use crate::format::{FileFormat, LineEnding};
+use crate::{BumpLevel, ManifestError, Metadata};
+
+/// A single `Cargo.toml` parsed from a string.
+///
+/// Unlike `Metadata`, nothing is known about the workspace, so keys inherited with
+/// `key.workspace = true` cannot be resolved; e.g. `version` returns `None` for them.
+/// Use `to_string` to get the edited manifest back.
+#[derive(Clone, Debug)]
+pub struct SingleManifest {
+    raw_map: toml_edit::DocumentMut,
+    format: FileFormat,
+}
+
+impl SingleManifest {
+    /// Parse the manifest with the given content.
+    ///
+    /// A parse error is reported as `ManifestError::TomlParse` with the path `<string>`.
+    pub fn from_manifest_str(toml: &str) -> Result<Self, ManifestError> {
+        let raw_map =
+            toml.parse::<toml_edit::DocumentMut>()
+                .map_err(|source| ManifestError::TomlParse {
+                    path: PathBuf::from("<string>"),
+                    source,
+                })?;
+        Ok(SingleManifest {
+            raw_map,
+            format: FileFormat::detect(toml, LineEnding::Auto),
+        })
+    }
+
+    /// Return `name` in `[package]`.
+    pub fn name(&self) -> Option<&str> {
+        self.raw_map.get("package")?.get("name")?.as_str()
+    }
+
+    /// Return `version` in `[package]`.
+    pub fn version(&self) -> Option<semver::Version> {
+        let raw_version = self.raw_map.get("package")?.get("version")?;
+        semver::Version::parse(raw_version.as_str()?).ok()
+    }
+
+    /// Set `version` in `[package]`. Return the previous version.
+    pub fn set_version(&mut self, version: semver::Version) -> Option<semver::Version> {
+        let raw_version = self.version_item_mut()?;
+        let old_version = semver::Version::parse(raw_version.as_str()?).ok()?;
+        crate::set_string(raw_version, version.to_string());
+        Some(old_version)
+    }
+
+    /// Bump the given part of `version` in `[package]`. Return the new version.
+    pub fn bump_version(&mut self, level: BumpLevel) -> Option<semver::Version> {
+        Metadata::edit_raw_version(self.version_item_mut()?, |ver| {
+            Metadata::increment_version(ver, level)
+        })
+    }
+
+    /// Return the dependencies in the table of the given kind paired with their version
+    /// requirements, as written in the manifest. See `Metadata::dependencies_of_kind`.
+    pub fn dependencies_of_kind(&self, kind: DependencyKind) -> Vec<(String, String)> {
+        let deps = match self
+            .raw_map
+            .get(kind.table_name())
+            .and_then(|deps| deps.as_table_like())
+        {
+            Some(deps) => deps,
+            None => return Vec::new(),
+        };
+        deps.iter()
+            .map(|(name, dep)| (name.to_owned(), dependency::display_requirement(dep)))
+            .collect()
+    }
+
+    /// Set the requirement of the dependency `dep` in `[dependencies]` to `req`.
+    /// Return `None` if the dependency does not exist or is inherited with
+    /// `dep.workspace = true`.
+    pub fn set_dependency_version(&mut self, dep: &str, req: &str) -> Option<()> {
+        let raw_dep = self
+            .raw_map
+            .get_mut("dependencies")?
+            .as_table_like_mut()?
+            .get_mut(dep)
+            .filter(|raw_dep| !crate::is_inherited(raw_dep))?;
+        dependency::set_requirement(raw_dep, req);
+        Some(())
+    }
+
+    /// Add the dependency described by `dep` to the table of the given kind, creating the
+    /// table if needed. An existing entry with the same name is replaced.
+    ///
+    /// Return an error if `dep` is invalid, see `Metadata::set_dependency`.
+    pub fn add_dependency_with(
+        &mut self,
+        kind: DependencyKind,
+        dep_name: &str,
+        dep: &DependencyBuilder,
+    ) -> Result<(), ManifestError> {
+        dep.validate()?;
+        let deps = self
+            .raw_map
+            .entry(kind.table_name())
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .ok_or_else(|| ManifestError::InvalidValue {
+                key: kind.table_name().to_owned(),
+                value: String::new(),
+                reason: "is not a table".to_owned(),
+            })?;
+        deps.insert(dep_name, dep.to_item());
+        Ok(())
+    }
+
+    /// Remove `dep_name` from the table of the given kind.
+    /// Return `true` if the dependency was removed.
+    pub fn remove_dependency_with(&mut self, kind: DependencyKind, dep_name: &str) -> bool {
+        self.raw_map
+            .get_mut(kind.table_name())
+            .and_then(|deps| deps.as_table_like_mut())
+            .and_then(|deps| deps.remove(dep_name))
+            .is_some()
+    }
+
+    fn version_item_mut(&mut self) -> Option<&mut toml_edit::Item> {
+        self.raw_map
+            .get_mut("package")?
+            .as_table_like_mut()?
+            .get_mut("version")
+    }
+}
+
+impl FromStr for SingleManifest {
+    type Err = ManifestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_manifest_str(s)
+    }
+}
+
+impl fmt::Display for SingleManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format.apply(self.raw_map.to_string()))
+    }
+}
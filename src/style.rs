@@ -0,0 +1,71 @@
+//! Layout of the values written by the editing methods.
+
+use crate::Metadata;
+
+/// How newly written values are laid out, to match the settings of a TOML formatter
+/// such as `taplo` that also runs on the manifests.
+///
+/// Only values written by the editing methods are affected; the rest of each manifest
+/// is kept as is.
+#[derive(Clone, Debug)]
+pub struct TomlStyle {
+    /// Write an array one element per line if it would be longer than this many characters
+    /// on a single line, e.g. `features` or `authors`. `None` keeps every array on one line.
+    pub max_array_width: Option<usize>,
+    /// The indentation of the elements of an array written one element per line.
+    pub array_indent: String,
+    /// Whether an array written one element per line ends with a trailing comma.
+    pub array_trailing_comma: bool,
+    /// Write a new dependency as a table of its own, e.g. `[dependencies.foo]`, if it would
+    /// be longer than this many characters as an inline table. `None` keeps every
+    /// dependency inline.
+    pub max_inline_table_width: Option<usize>,
+}
+
+impl Default for TomlStyle {
+    fn default() -> Self {
+        TomlStyle {
+            max_array_width: None,
+            array_indent: "    ".to_owned(),
+            array_trailing_comma: true,
+            max_inline_table_width: None,
+        }
+    }
+}
+
+impl TomlStyle {
+    /// Lay out `array` as configured, assuming it is laid out on one line.
+    pub(crate) fn format_array(&self, array: &mut toml_edit::Array) {
+        let max_width = match self.max_array_width {
+            Some(max_width) => max_width,
+            None => return,
+        };
+        array.fmt();
+        if array.is_empty() || array.to_string().trim().len() <= max_width {
+            return;
+        }
+        for value in array.iter_mut() {
+            value
+                .decor_mut()
+                .set_prefix(format!("\n{}", self.array_indent));
+            value.decor_mut().set_suffix("");
+        }
+        array.set_trailing("\n");
+        array.set_trailing_comma(self.array_trailing_comma);
+    }
+
+    /// Return `true` if the dependency entry `dep` should be written as a table of its own.
+    pub(crate) fn is_too_wide(&self, dep: &toml_edit::Item) -> bool {
+        match (self.max_inline_table_width, dep.as_inline_table()) {
+            (Some(max_width), Some(dep)) => dep.to_string().trim().len() > max_width,
+            _ => false,
+        }
+    }
+}
+
+impl Metadata {
+    /// Lay out the values written from now on as configured by `style`.
+    pub fn set_output_style(&mut self, style: TomlStyle) {
+        self.style = style;
+    }
+}
@@ -0,0 +1,129 @@
+//! Checks for common mistakes in manifests before they are written back.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{dependency, Metadata, EDITIONS};
+
+/// A mistake found in the manifest of a workspace member by `Metadata::validate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The name of the package.
+    pub package: String,
+    /// The offending key, e.g. `version` or `dependencies.foo`.
+    pub key: String,
+    /// What is wrong with the value.
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` of `{}`: {}", self.key, self.package, self.message)
+    }
+}
+
+impl Error for ValidationError {}
+
+impl Metadata {
+    /// Check the manifests of the workspace members for mistakes that Cargo would only
+    /// report at `cargo publish` time or later:
+    ///
+    /// - a missing `version` in a package that may be published,
+    /// - a `version` that is not a string holding a semantic version,
+    /// - an `edition` other than `2015`, `2018`, `2021` and `2024`,
+    /// - a dependency that is neither a string nor a table,
+    /// - a dependency on a `registry` that is not one of `known_registries`,
+    /// - a `links` value shared with another workspace member.
+    ///
+    /// Keys inherited from the workspace with `key.workspace = true` are not checked.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        let registries = self.known_registries();
+        let mut links = HashMap::new();
+        for name in self.workspace_members() {
+            let mut report = |key: &str, message: &str| {
+                errors.push(ValidationError {
+                    package: name.to_owned(),
+                    key: key.to_owned(),
+                    message: message.to_owned(),
+                })
+            };
+
+            let raw_package = self.package(name);
+            match raw_package.and_then(|raw_package| raw_package.get("version")) {
+                None if self.is_publishable(name) => {
+                    report("version", "missing, but `publish` is not `false`")
+                }
+                None => {}
+                Some(version) if crate::is_inherited(version) => {}
+                Some(version) => match version.as_str() {
+                    None => report("version", "not a string"),
+                    Some(version) => {
+                        if let Err(e) = semver::Version::parse(version) {
+                            report("version", &format!("invalid version: {}", e));
+                        }
+                    }
+                },
+            }
+
+            if let Some(edition) = raw_package.and_then(|raw_package| raw_package.get("edition")) {
+                let is_known = edition
+                    .as_str()
+                    .is_some_and(|edition| EDITIONS.contains(&edition));
+                if !crate::is_inherited(edition) && !is_known {
+                    report(
+                        "edition",
+                        &format!("expected one of {}", EDITIONS.join(", ")),
+                    );
+                }
+            }
+
+            if let Some(value) = self.links(name) {
+                match links.get(value) {
+                    Some(other) => report("links", &format!("already used by `{}`", other)),
+                    None => {
+                        links.insert(value, name);
+                    }
+                }
+            }
+
+            let raw_map = match self.manifest(name) {
+                Some(raw_map) => raw_map,
+                None => continue,
+            };
+            for (table_path, _, deps) in dependency::dependency_tables(raw_map) {
+                let deps = match deps.as_table_like() {
+                    Some(deps) => deps,
+                    None => continue,
+                };
+                for (dep_name, dep) in deps.iter() {
+                    if !dep.is_str() && !dep.is_table_like() {
+                        report(
+                            &format!("{}.{}", table_path, dep_name),
+                            "expected a version string or a table",
+                        );
+                    }
+                    let registry = dep.get("registry").and_then(|registry| registry.as_str());
+                    if let Some(registry) = registry {
+                        if registry != "crates-io" && !registries.iter().any(|r| r == registry) {
+                            report(
+                                &format!("{}.{}", table_path, dep_name),
+                                &format!(
+                                    "registry `{}` is not declared in the Cargo configuration",
+                                    registry
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
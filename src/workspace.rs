@@ -0,0 +1,281 @@
+//! Editing of the `[workspace]` table of the root manifest.
+
+use std::path::Path;
+
+use crate::dependency;
+use crate::{Change, ManifestError, Metadata};
+
+/// Versions of the feature resolver known to Cargo.
+const RESOLVERS: [&str; 3] = ["1", "2", "3"];
+
+impl Metadata {
+    /// Return the entries of `[workspace] members` of the root manifest as written,
+    /// including glob patterns such as `crates/*`.
+    pub fn workspace_members_globs(&self) -> Vec<String> {
+        let members = self
+            .root_manifest()
+            .and_then(|raw_map| raw_map.get("workspace"))
+            .and_then(|workspace| workspace.get("members"));
+        crate::string_array(members).unwrap_or_default()
+    }
+
+    /// Add `relative_path` to `[workspace] members` of the root manifest, keeping the list
+    /// sorted. The table and the array are created if needed.
+    /// Return `true` if the member was added, `false` if it was already listed.
+    pub fn add_workspace_member(&mut self, relative_path: &str) -> bool {
+        let _scope = self.edit_scope();
+        let members = match self
+            .root_manifest_mut()
+            .and_then(|raw_map| {
+                raw_map
+                    .entry("workspace")
+                    .or_insert_with(toml_edit::table)
+                    .as_table_like_mut()
+            })
+            .and_then(|workspace| {
+                workspace
+                    .entry("members")
+                    .or_insert_with(|| toml_edit::value(toml_edit::Array::new()))
+                    .as_array_mut()
+            }) {
+            Some(members) => members,
+            None => return false,
+        };
+        if members
+            .iter()
+            .any(|member| member.as_str() == Some(relative_path))
+        {
+            return false;
+        }
+
+        let multi_line = is_multi_line(members);
+        let index = members
+            .iter()
+            .position(|member| member.as_str().is_some_and(|member| member > relative_path))
+            .unwrap_or_else(|| members.len());
+        let decor = members.iter().last().map(|member| member.decor().clone());
+        members.insert(index, relative_path);
+        match decor {
+            Some(decor) if multi_line => {
+                if let Some(member) = members.get_mut(index) {
+                    *member.decor_mut() = decor;
+                }
+            }
+            _ => members.fmt(),
+        }
+        self.mark_root_dirty();
+        self.record_root_edit("workspace.members".to_owned());
+        true
+    }
+
+    /// Remove `relative_path` from `[workspace] members` of the root manifest.
+    /// Return `true` if the member was removed.
+    pub fn remove_workspace_member(&mut self, relative_path: &str) -> bool {
+        let _scope = self.edit_scope();
+        let members = match self
+            .root_manifest_mut()
+            .and_then(|raw_map| raw_map.get_mut("workspace"))
+            .and_then(|workspace| workspace.as_table_like_mut())
+            .and_then(|workspace| workspace.get_mut("members"))
+            .and_then(|members| members.as_array_mut())
+        {
+            Some(members) => members,
+            None => return false,
+        };
+        let len = members.len();
+        let multi_line = is_multi_line(members);
+        members.retain(|member| member.as_str() != Some(relative_path));
+        if members.len() == len {
+            return false;
+        }
+        if !multi_line {
+            members.fmt();
+        }
+        self.mark_root_dirty();
+        self.record_root_edit("workspace.members".to_owned());
+        true
+    }
+
+    /// Return the entries of `[workspace] default-members` of the root manifest as written.
+    pub fn default_members(&self) -> Vec<String> {
+        let default_members = self
+            .root_manifest()
+            .and_then(|raw_map| raw_map.get("workspace"))
+            .and_then(|workspace| workspace.get("default-members"));
+        crate::string_array(default_members).unwrap_or_default()
+    }
+
+    /// Overwrite `[workspace] default-members` of the root manifest, laying the array out
+    /// like `members`. An empty slice removes the key.
+    ///
+    /// Return an error if an entry is not the path of a workspace member relative to the
+    /// workspace root, which Cargo rejects.
+    pub fn set_default_members(&mut self, default_members: &[String]) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        for default_member in default_members {
+            if !self.is_member_path(default_member) {
+                return Err(ManifestError::InvalidValue {
+                    key: "default-members".to_owned(),
+                    value: default_member.clone(),
+                    reason: "is not a workspace member".to_owned(),
+                });
+            }
+        }
+
+        let mut array = default_members.iter().collect::<toml_edit::Array>();
+        let members = self
+            .root_manifest()
+            .and_then(|raw_map| raw_map.get("workspace"))
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(|members| members.as_array());
+        match members {
+            Some(members) if is_multi_line(members) => {
+                let decor = members.iter().last().map(|member| member.decor().clone());
+                for value in array.iter_mut() {
+                    if let Some(decor) = &decor {
+                        *value.decor_mut() = decor.clone();
+                    }
+                }
+                array.set_trailing(members.trailing().clone());
+                array.set_trailing_comma(members.trailing_comma());
+            }
+            Some(_) => array.fmt(),
+            None => self.style.format_array(&mut array),
+        }
+
+        let not_a_table = || ManifestError::InvalidValue {
+            key: "workspace".to_owned(),
+            value: String::new(),
+            reason: "is not a table".to_owned(),
+        };
+        if default_members.is_empty() {
+            let removed = self
+                .root_manifest_mut()
+                .and_then(|raw_map| raw_map.get_mut("workspace"))
+                .and_then(|workspace| workspace.as_table_like_mut())
+                .and_then(|workspace| workspace.remove("default-members"));
+            if removed.is_none() {
+                return Ok(());
+            }
+        } else {
+            self.root_manifest_mut()
+                .and_then(|raw_map| {
+                    raw_map
+                        .entry("workspace")
+                        .or_insert_with(toml_edit::table)
+                        .as_table_like_mut()
+                })
+                .ok_or_else(not_a_table)?
+                .insert("default-members", toml_edit::value(array));
+        }
+        self.mark_root_dirty();
+        self.record_root_edit("workspace.default-members".to_owned());
+        Ok(())
+    }
+
+    /// Set the version of the feature resolver, e.g. `2`, in the root manifest: in
+    /// `[workspace]` if the root manifest has one, e.g. a virtual manifest, otherwise in
+    /// `[package]` of the single package.
+    ///
+    /// Return an error if `version` is not one of `1`, `2` and `3`.
+    pub fn set_resolver(&mut self, version: &str) -> Result<(), ManifestError> {
+        let _scope = self.edit_scope();
+        if !RESOLVERS.contains(&version) {
+            return Err(ManifestError::InvalidValue {
+                key: "resolver".to_owned(),
+                value: version.to_owned(),
+                reason: format!("expected one of {}", RESOLVERS.join(", ")),
+            });
+        }
+        let key = match self.root_manifest() {
+            Some(raw_map) if raw_map.contains_key("workspace") => "workspace",
+            _ => "package",
+        };
+        let table = self
+            .root_manifest_mut()
+            .and_then(|raw_map| raw_map.get_mut(key)?.as_table_like_mut());
+        let table = table.ok_or_else(|| ManifestError::InvalidValue {
+            key: "resolver".to_owned(),
+            value: version.to_owned(),
+            reason: "the root manifest has neither `[workspace]` nor `[package]`".to_owned(),
+        })?;
+        match table.get_mut("resolver") {
+            Some(resolver) if resolver.as_str() == Some(version) => return Ok(()),
+            Some(resolver) => crate::set_string(resolver, version.to_owned()),
+            None => {
+                table.insert("resolver", toml_edit::value(version));
+            }
+        }
+        self.mark_root_dirty();
+        self.record_root_edit(format!("{}.resolver", key));
+        Ok(())
+    }
+
+    /// Return the requirement of `dep` in `[workspace.dependencies]` of the root manifest.
+    pub fn workspace_dependency_version(&self, dep: &str) -> Option<String> {
+        let raw_dep = self.workspace_dependencies()?.get(dep)?;
+        dependency::requirement(raw_dep).map(ToOwned::to_owned)
+    }
+
+    /// Set the requirement of `dep` in `[workspace.dependencies]` of the root manifest to
+    /// `req`, which affects every member that declares `dep = { workspace = true }`.
+    ///
+    /// Both `dep = "1.0"` and `dep = { version = "1.0", ... }` are supported.
+    /// Return `None` if the dependency does not exist.
+    pub fn set_workspace_dependency(&mut self, dep: &str, req: &str) -> Option<()> {
+        let _scope = self.edit_scope();
+        let raw_dep = self.workspace_dependencies_mut()?.get_mut(dep)?;
+        let old_req = dependency::display_requirement(raw_dep);
+        if dependency::set_requirement(raw_dep, req) {
+            self.mark_root_dirty();
+            self.record_change(Change::WorkspaceDependency {
+                name: dep.to_owned(),
+                from: old_req,
+                to: req.to_owned(),
+            });
+        }
+        Some(())
+    }
+
+    /// Return `true` if `relative_path` is the directory of a workspace member relative to
+    /// the workspace root, e.g. `crates/foo` or `.` for the root package.
+    fn is_member_path(&self, relative_path: &str) -> bool {
+        let relative_path = Path::new(relative_path);
+        let workspace_root = &self.metadata.workspace_root;
+        self.metadata
+            .packages
+            .iter()
+            .filter(|p| self.is_member(&p.id))
+            .filter_map(|p| p.manifest_path.parent()?.strip_prefix(workspace_root).ok())
+            .any(|dir| {
+                dir == relative_path
+                    || (dir.as_os_str().is_empty() && relative_path == Path::new("."))
+            })
+    }
+
+    fn workspace_dependencies(&self) -> Option<&dyn toml_edit::TableLike> {
+        self.root_manifest()?
+            .get("workspace")?
+            .get("dependencies")?
+            .as_table_like()
+    }
+
+    pub(crate) fn workspace_dependencies_mut(&mut self) -> Option<&mut dyn toml_edit::TableLike> {
+        self.root_manifest_mut()?
+            .get_mut("workspace")?
+            .as_table_like_mut()?
+            .get_mut("dependencies")?
+            .as_table_like_mut()
+    }
+}
+
+/// Return `true` if the elements of `array` are written on their own lines.
+fn is_multi_line(array: &toml_edit::Array) -> bool {
+    array.iter().any(|value| {
+        value
+            .decor()
+            .prefix()
+            .and_then(|prefix| prefix.as_str())
+            .is_some_and(|prefix| prefix.contains('\n'))
+    })
+}
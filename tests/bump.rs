@@ -0,0 +1,178 @@
+mod common;
+
+use common::Fixture;
+use manifest_editor::{BumpLevel, BumpOptions, Change};
+
+#[test]
+fn bump_inherited_version_bumps_workspace_version() {
+    let fixture = Fixture::new(&[
+        (
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"foo\"]\n\n[workspace.package]\nversion = \"1.2.3\"\n",
+        ),
+        (
+            "foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion.workspace = true\n",
+        ),
+    ]);
+    let mut metadata = fixture.metadata();
+    assert_eq!(
+        metadata.bump_minor_version("foo"),
+        Some(semver::Version::new(1, 3, 0))
+    );
+    metadata.dump().unwrap();
+
+    assert_eq!(
+        fixture.read("Cargo.toml"),
+        "[workspace]\nmembers = [\"foo\"]\n\n[workspace.package]\nversion = \"1.3.0\"\n"
+    );
+    assert_eq!(
+        fixture.read("foo/Cargo.toml"),
+        "[package]\nname = \"foo\"\nversion.workspace = true\n"
+    );
+}
+
+fn build_metadata_fixture() -> Fixture {
+    Fixture::new(&[(
+        "Cargo.toml",
+        "[package]\nname = \"foo\"\nversion = \"1.2.3+build5\"\n",
+    )])
+}
+
+#[test]
+fn bump_drops_build_metadata_by_default() {
+    let fixture = build_metadata_fixture();
+    let mut metadata = fixture.metadata();
+    let version = metadata.bump_version_with("foo", BumpLevel::Patch, BumpOptions::default());
+    assert_eq!(version, Some(semver::Version::parse("1.2.4").unwrap()));
+    metadata.dump().unwrap();
+
+    assert!(fixture.read("Cargo.toml").contains("version = \"1.2.4\"\n"));
+}
+
+#[test]
+fn bump_preserves_build_metadata() {
+    let fixture = build_metadata_fixture();
+    let mut metadata = fixture.metadata();
+    let options = BumpOptions {
+        preserve_build_metadata: true,
+    };
+    let version = metadata.bump_version_with("foo", BumpLevel::Patch, options);
+    assert_eq!(
+        version,
+        Some(semver::Version::parse("1.2.4+build5").unwrap())
+    );
+    metadata.dump().unwrap();
+
+    assert!(fixture
+        .read("Cargo.toml")
+        .contains("version = \"1.2.4+build5\"\n"));
+}
+
+#[test]
+fn bump_with_dependents_updates_target_dependencies() {
+    let fixture = Fixture::new(&[
+        ("Cargo.toml", "[workspace]\nmembers = [\"foo\", \"bar\"]\n"),
+        (
+            "foo/Cargo.toml",
+            r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[target.'cfg(windows)'.dependencies]
+bar = { path = "../bar", version = "0.3" }
+"#,
+        ),
+        (
+            "bar/Cargo.toml",
+            "[package]\nname = \"bar\"\nversion = \"0.3.1\"\n",
+        ),
+    ]);
+    let mut metadata = fixture.metadata();
+    metadata.bump_minor_version_with_dependents("bar").unwrap();
+    metadata.dump().unwrap();
+
+    assert!(fixture
+        .read("foo/Cargo.toml")
+        .contains("bar = { path = \"../bar\", version = \"0.4\" }\n"));
+}
+
+#[test]
+fn bump_with_dependents_updates_workspace_dependencies() {
+    let fixture = Fixture::new(&[
+        (
+            "Cargo.toml",
+            r#"[workspace]
+members = ["foo", "bar"]
+
+[workspace.dependencies]
+bar = { path = "bar", version = "0.3" }
+"#,
+        ),
+        (
+            "foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n\n[dependencies]\nbar.workspace = true\n",
+        ),
+        (
+            "bar/Cargo.toml",
+            "[package]\nname = \"bar\"\nversion = \"0.3.1\"\n",
+        ),
+    ]);
+    let mut metadata = fixture.metadata();
+    metadata.bump_minor_version_with_dependents("bar").unwrap();
+    assert!(metadata
+        .pending_changes()
+        .contains(&Change::WorkspaceDependency {
+            name: "bar".to_owned(),
+            from: "0.3".to_owned(),
+            to: "0.4".to_owned(),
+        }));
+    metadata.dump().unwrap();
+
+    assert!(fixture
+        .read("Cargo.toml")
+        .contains("bar = { path = \"bar\", version = \"0.4\" }\n"));
+}
+
+#[test]
+fn bump_inherited_version_updates_dependents_of_every_inheriting_member() {
+    let fixture = Fixture::new(&[
+        (
+            "Cargo.toml",
+            r#"[workspace]
+members = ["a", "b", "c"]
+
+[workspace.package]
+version = "0.3.1"
+"#,
+        ),
+        (
+            "a/Cargo.toml",
+            "[package]\nname = \"a\"\nversion.workspace = true\n",
+        ),
+        (
+            "b/Cargo.toml",
+            "[package]\nname = \"b\"\nversion.workspace = true\n",
+        ),
+        (
+            "c/Cargo.toml",
+            r#"[package]
+name = "c"
+version = "1.0.0"
+
+[dependencies]
+b = { path = "../b", version = "0.3" }
+"#,
+        ),
+    ]);
+    let mut metadata = fixture.metadata();
+    assert_eq!(
+        metadata.bump_minor_version_with_dependents("a"),
+        Some(semver::Version::new(0, 4, 0))
+    );
+    metadata.dump().unwrap();
+
+    assert!(fixture
+        .read("c/Cargo.toml")
+        .contains("b = { path = \"../b\", version = \"0.4\" }\n"));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::Fixture;
+use manifest_editor::{toml_edit, Change, DepStyle};
+
+fn workspace() -> Fixture {
+    Fixture::new(&[
+        (
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"foo\"]\n\n[profile.release]\nlto = false\n",
+        ),
+        (
+            "foo/Cargo.toml",
+            r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[features]
+std = []
+
+[dependencies]
+zeta = "1"
+alpha = { version = "2", optional = true }
+"#,
+        ),
+    ])
+}
+
+fn other(package: Option<&str>, key: &str) -> Change {
+    Change::Other {
+        package: package.map(ToOwned::to_owned),
+        key: key.to_owned(),
+    }
+}
+
+#[test]
+fn pending_changes_reports_other_edits() {
+    let fixture = workspace();
+    let mut metadata = fixture.metadata();
+    metadata
+        .add_feature("foo", "cli", &["std".to_owned()])
+        .unwrap();
+    metadata.sort_dependencies("foo").unwrap();
+    metadata
+        .set_dependency_style("foo", DepStyle::Detailed)
+        .unwrap();
+    metadata
+        .set_profile_setting("release", "lto", true.into())
+        .unwrap();
+    metadata.set_resolver("2").unwrap();
+
+    assert_eq!(
+        metadata.pending_changes(),
+        vec![
+            other(Some("foo"), "features.cli"),
+            other(Some("foo"), "dependencies"),
+            other(None, "profile.release.lto"),
+            other(None, "workspace.resolver"),
+        ]
+    );
+    assert_eq!(metadata.modified_manifest_count(), 2);
+}
+
+#[test]
+fn pending_changes_reports_raw_edits_by_key() {
+    let fixture = workspace();
+    let mut metadata = fixture.metadata();
+    metadata
+        .edit_raw("foo", |manifest| {
+            manifest.remove("features");
+            manifest.insert("lib", toml_edit::table());
+        })
+        .unwrap();
+
+    assert_eq!(
+        metadata.pending_changes(),
+        vec![other(Some("foo"), "lib"), other(Some("foo"), "features")]
+    );
+}
+
+#[test]
+fn pending_changes_ignores_noop_edits() {
+    let fixture = workspace();
+    let mut metadata = fixture.metadata();
+    assert!(!metadata.remove_feature("foo", "cli"));
+    metadata.edit_raw("foo", |_| {}).unwrap();
+
+    assert_eq!(metadata.pending_changes(), Vec::new());
+    assert_eq!(metadata.modified_manifest_count(), 0);
+}
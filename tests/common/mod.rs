@@ -0,0 +1,58 @@
+//! Workspaces written to a temporary directory for the tests.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use manifest_editor::Metadata;
+
+/// A workspace in a temporary directory, removed when dropped.
+pub struct Fixture {
+    dir: tempfile::TempDir,
+}
+
+impl Fixture {
+    /// Write `files`, given as paths relative to the workspace root and their contents.
+    ///
+    /// Every manifest with a `[package]` gets an empty `src/lib.rs` unless `files` has one,
+    /// so that `cargo metadata` accepts it.
+    pub fn new(files: &[(&str, &str)]) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            write(&dir.path().join(path), content);
+        }
+        for (path, content) in files {
+            let path = Path::new(path);
+            if path.file_name().is_some_and(|f| f == "Cargo.toml") && content.contains("[package]")
+            {
+                let lib = dir.path().join(path.with_file_name("src/lib.rs"));
+                if !lib.exists() {
+                    write(&lib, "");
+                }
+            }
+        }
+        Fixture { dir }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn path(&self, path: &str) -> PathBuf {
+        self.dir.path().join(path)
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        fs::read_to_string(self.path(path)).unwrap()
+    }
+
+    pub fn metadata(&self) -> Metadata {
+        Metadata::from_dir_no_deps(self.root()).unwrap()
+    }
+}
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::Fixture;
+use manifest_editor::ManifestError;
+
+#[test]
+fn empty_keywords_and_categories_remove_the_keys() {
+    let fixture = Fixture::new(&[(
+        "Cargo.toml",
+        r#"[package]
+name = "foo"
+version = "0.1.0"
+keywords = ["cli"]
+categories = ["command-line-utilities"]
+"#,
+    )]);
+    let mut metadata = fixture.metadata();
+    metadata.set_keywords("foo", &[]).unwrap();
+    metadata.set_categories("foo", &[]).unwrap();
+    metadata.dump().unwrap();
+
+    assert_eq!(
+        fixture.read("Cargo.toml"),
+        "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n"
+    );
+}
+
+#[test]
+fn setting_the_same_value_is_a_noop() {
+    let fixture = Fixture::new(&[(
+        "Cargo.toml",
+        r#"[package]
+name = "foo"
+version = "0.1.0"
+publish = false
+keywords = [
+    "cli",
+    "toml",
+]
+"#,
+    )]);
+    let mut metadata = fixture.metadata();
+    metadata.set_publish("foo", false).unwrap();
+    metadata
+        .set_keywords("foo", &["cli".to_owned(), "toml".to_owned()])
+        .unwrap();
+
+    assert_eq!(metadata.modified_manifest_count(), 0);
+    assert!(metadata.pending_changes().is_empty());
+    assert!(!metadata.undo());
+}
+
+#[test]
+fn add_author_rejects_inherited_authors() {
+    let fixture = Fixture::new(&[
+        (
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"foo\"]\n\n[workspace.package]\nauthors = [\"a\"]\n",
+        ),
+        (
+            "foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\nauthors.workspace = true\n",
+        ),
+    ]);
+    let mut metadata = fixture.metadata();
+    assert!(matches!(
+        metadata.add_author("foo", "b"),
+        Err(ManifestError::InvalidValue { key, .. }) if key == "authors"
+    ));
+    assert!(matches!(
+        metadata.add_author("missing", "b"),
+        Err(ManifestError::PackageNotFound(_))
+    ));
+    assert_eq!(metadata.modified_manifest_count(), 0);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::Fixture;
+use manifest_editor::{DepStyle, DependencyBuilder, DependencyKind, ManifestError, SingleManifest};
+
+#[test]
+fn rename_package_rewrites_features_of_dependents() {
+    let fixture = Fixture::new(&[
+        ("Cargo.toml", "[workspace]\nmembers = [\"foo\", \"baz\"]\n"),
+        (
+            "foo/Cargo.toml",
+            r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+baz = { path = "../baz", optional = true }
+
+[features]
+std = ["baz?/std"]
+full = ["dep:baz", "baz/std"]
+"#,
+        ),
+        (
+            "baz/Cargo.toml",
+            "[package]\nname = \"baz\"\nversion = \"0.1.0\"\n\n[features]\nstd = []\n",
+        ),
+    ]);
+    let mut metadata = fixture.metadata();
+    metadata.rename_package("baz", "qux").unwrap();
+    metadata.dump().unwrap();
+
+    assert_eq!(
+        fixture.read("foo/Cargo.toml"),
+        r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+qux = { path = "../baz", optional = true }
+
+[features]
+std = ["qux?/std"]
+full = ["dep:qux", "qux/std"]
+"#
+    );
+}
+
+const INLINE_DEPENDENCIES: &str = r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+# The first dependency.
+alpha = "1"
+
+# Pinned until the next release.
+zeta = { version = "2", features = ["std"] } # keep in sync with alpha
+"#;
+
+const DETAILED_DEPENDENCIES: &str = r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+# The first dependency.
+alpha = "1"
+
+# Pinned until the next release.
+[dependencies.zeta] # keep in sync with alpha
+version = "2"
+features = ["std"]
+"#;
+
+#[test]
+fn set_dependency_style_keeps_comments() {
+    let fixture = Fixture::new(&[("Cargo.toml", INLINE_DEPENDENCIES)]);
+    let mut metadata = fixture.metadata();
+    metadata
+        .set_dependency_style("foo", DepStyle::Detailed)
+        .unwrap();
+    metadata.dump().unwrap();
+    assert_eq!(fixture.read("Cargo.toml"), DETAILED_DEPENDENCIES);
+
+    metadata
+        .set_dependency_style("foo", DepStyle::Inline)
+        .unwrap();
+    metadata.dump().unwrap();
+    assert_eq!(fixture.read("Cargo.toml"), INLINE_DEPENDENCIES);
+}
+
+#[test]
+fn add_dependency_with_validates_builder() {
+    let manifest = "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n";
+    let fixture = Fixture::new(&[("Cargo.toml", manifest)]);
+    let mut metadata = fixture.metadata();
+    let kind = DependencyKind::Normal;
+
+    let two_sources = DependencyBuilder::new().git("x").tag("t").path("y");
+    assert!(matches!(
+        metadata.add_dependency_with("foo", kind, "zz", &two_sources),
+        Err(ManifestError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        metadata.add_dependency_with("foo", kind, "empty", &DependencyBuilder::new()),
+        Err(ManifestError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        metadata.add_dependency_with("bar", kind, "zz", &DependencyBuilder::new().version("1")),
+        Err(ManifestError::PackageNotFound(_))
+    ));
+    assert_eq!(metadata.modified_manifest_count(), 0);
+
+    let git = DependencyBuilder::new()
+        .git("https://example.com/zz")
+        .tag("t");
+    metadata
+        .add_dependency_with("foo", kind, "zz", &git)
+        .unwrap();
+    assert_eq!(
+        metadata.dependencies("foo").unwrap(),
+        [("zz".to_owned(), "*".to_owned())]
+    );
+}
+
+#[test]
+fn set_dependency_version_refuses_inherited_dependencies() {
+    let manifest = r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+serde = { workspace = true }
+"#;
+    let fixture = Fixture::new(&[
+        (
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"foo\"]\n\n[workspace.dependencies]\nserde = \"1\"\n",
+        ),
+        ("foo/Cargo.toml", manifest),
+    ]);
+    let mut metadata = fixture.metadata();
+    assert_eq!(
+        metadata.set_dependency_version("foo", "serde", "1.0.200"),
+        None
+    );
+    assert_eq!(metadata.modified_manifest_count(), 0);
+
+    let mut single = SingleManifest::from_manifest_str(manifest).unwrap();
+    assert_eq!(single.set_dependency_version("serde", "1.0.200"), None);
+    assert_eq!(single.to_string(), manifest);
+}
+
+#[test]
+fn sort_dependencies_ignores_dependency_tables() {
+    let manifest = r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+z = "1"
+
+[dependencies.a]
+version = "1"
+"#;
+    let fixture = Fixture::new(&[("Cargo.toml", manifest)]);
+    let mut metadata = fixture.metadata();
+    metadata.sort_dependencies("foo").unwrap();
+
+    assert_eq!(metadata.modified_manifest_count(), 0);
+    assert!(metadata.pending_changes().is_empty());
+}
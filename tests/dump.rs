@@ -0,0 +1,134 @@
+mod common;
+
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use common::Fixture;
+
+#[test]
+fn bump_keeps_comments_and_key_order() {
+    let manifest = r#"# The license header.
+
+[package]
+# Bumped by the release script.
+name = "foo"
+edition = "2018"
+version = "0.1.0" # keep in sync with the changelog
+description = "Foo."
+
+[dependencies]
+# Pinned until the next major release.
+zeta = "1"
+alpha = { version = "2", features = ["std"] } # sorted by hand
+"#;
+    let fixture = Fixture::new(&[("Cargo.toml", manifest)]);
+    let mut metadata = fixture.metadata();
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.dump().unwrap();
+
+    assert_eq!(
+        fixture.read("Cargo.toml"),
+        manifest.replace(r#""0.1.0""#, r#""0.1.1""#)
+    );
+}
+
+#[test]
+fn dump_leaves_unmodified_manifests_untouched() {
+    let fixture = Fixture::new(&[
+        ("Cargo.toml", "[workspace]\nmembers = [\"foo\", \"bar\"]\n"),
+        (
+            "foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+        ),
+        (
+            "bar/Cargo.toml",
+            "[package]\nname = \"bar\"\nversion = \"0.1.0\"\n",
+        ),
+    ]);
+    // Backdate the manifests so that a rewrite is visible whatever the mtime resolution is.
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    for path in &["foo/Cargo.toml", "bar/Cargo.toml"] {
+        let file = fs::File::options()
+            .write(true)
+            .open(fixture.path(path))
+            .unwrap();
+        file.set_modified(old).unwrap();
+    }
+    let mtime = |path: &str| {
+        fs::metadata(fixture.path(path))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+
+    let mut metadata = fixture.metadata();
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.dump().unwrap();
+
+    assert_ne!(mtime("foo/Cargo.toml"), old);
+    assert_eq!(mtime("bar/Cargo.toml"), old);
+}
+
+#[test]
+fn noop_edit_keeps_arrays_of_tables() {
+    let manifest = r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[[bin]]
+name = "zeta"
+path = "src/bin/zeta.rs"
+required-features = [ "cli" ]   # spaced by hand
+
+[features]
+cli = []
+
+[[bin]]
+name = "alpha"
+path = "src/bin/alpha.rs"
+"#;
+    let fixture = Fixture::new(&[
+        ("Cargo.toml", manifest),
+        ("src/bin/zeta.rs", "fn main() {}\n"),
+        ("src/bin/alpha.rs", "fn main() {}\n"),
+    ]);
+    let mut metadata = fixture.metadata();
+    metadata.bump_patch_version("foo").unwrap();
+    metadata
+        .set_version("foo", semver::Version::new(0, 1, 0))
+        .unwrap();
+    assert_eq!(metadata.modified_manifest_count(), 1);
+    metadata.dump().unwrap();
+
+    assert_eq!(fixture.read("Cargo.toml"), manifest);
+}
+
+#[cfg(unix)]
+#[test]
+fn dump_keeps_permissions_and_symlinks() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let fixture = Fixture::new(&[
+        ("Cargo.toml", "[workspace]\nmembers = [\"foo\"]\n"),
+        (
+            "shared/foo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+        ),
+        ("foo/src/lib.rs", ""),
+    ]);
+    symlink("../shared/foo.toml", fixture.path("foo/Cargo.toml")).unwrap();
+    let target = fixture.path("shared/foo.toml");
+    fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+
+    let mut metadata = fixture.metadata();
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.dump().unwrap();
+
+    let link = fs::symlink_metadata(fixture.path("foo/Cargo.toml")).unwrap();
+    assert!(link.file_type().is_symlink());
+    assert!(fixture
+        .read("shared/foo.toml")
+        .contains("version = \"0.1.1\"\n"));
+    let mode = fs::metadata(&target).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use common::Fixture;
+use manifest_editor::BumpLevel;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+#[test]
+fn bump_changed_since_bumps_innermost_member() {
+    let fixture = Fixture::new(&[
+        (
+            "Cargo.toml",
+            r#"[package]
+name = "root"
+version = "0.1.0"
+
+[workspace]
+members = ["foo", "foo/nested", "foo-bar"]
+"#,
+        ),
+        (
+            "foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+        ),
+        (
+            "foo/nested/Cargo.toml",
+            "[package]\nname = \"nested\"\nversion = \"0.1.0\"\n",
+        ),
+        (
+            "foo-bar/Cargo.toml",
+            "[package]\nname = \"foo-bar\"\nversion = \"0.1.0\"\n",
+        ),
+    ]);
+    git(fixture.root(), &["init", "-q"]);
+    git(fixture.root(), &["add", "."]);
+    git(fixture.root(), &["commit", "-q", "-m", "init"]);
+    fs::write(fixture.path("foo/nested/src/lib.rs"), "// changed\n").unwrap();
+    fs::write(fixture.path("foo-bar/src/lib.rs"), "// changed\n").unwrap();
+
+    let mut metadata = fixture.metadata();
+    let mut bumped = metadata
+        .bump_changed_since("HEAD", BumpLevel::Patch)
+        .unwrap();
+    bumped.sort();
+
+    assert_eq!(bumped, ["foo-bar", "nested"]);
+    assert_eq!(metadata.version("foo"), Some(semver::Version::new(0, 1, 0)));
+}
+
+#[test]
+fn bump_changed_since_rejects_options_as_refs() {
+    let fixture = Fixture::new(&[(
+        "Cargo.toml",
+        "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+    )]);
+    git(fixture.root(), &["init", "-q"]);
+    git(fixture.root(), &["add", "."]);
+    git(fixture.root(), &["commit", "-q", "-m", "init"]);
+    let output = fixture.path("output");
+
+    let mut metadata = fixture.metadata();
+    let git_ref = format!("--output={}", output.display());
+    assert!(metadata
+        .bump_changed_since(&git_ref, BumpLevel::Patch)
+        .is_err());
+    assert!(!output.exists());
+}
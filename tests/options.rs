@@ -0,0 +1,24 @@
+mod common;
+
+use common::Fixture;
+use manifest_editor::{Metadata, MetadataOptions};
+
+#[test]
+fn options_combine_cargo_flags_and_file_name() {
+    let manifest = "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n";
+    let fixture = Fixture::new(&[("Cargo.toml", manifest), ("Cargo.toml.orig", manifest)]);
+    let options = MetadataOptions {
+        no_deps: true,
+        offline: true,
+        file_name: "Cargo.toml.orig".to_owned(),
+        ..MetadataOptions::default()
+    };
+    let mut metadata = Metadata::from_dir_with_options(fixture.root(), options).unwrap();
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.dump().unwrap();
+
+    assert_eq!(fixture.read("Cargo.toml"), manifest);
+    assert!(fixture
+        .read("Cargo.toml.orig")
+        .contains("version = \"0.1.1\"\n"));
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::Fixture;
+use manifest_editor::{ManifestError, Metadata};
+
+fn fixture() -> Fixture {
+    Fixture::new(&[
+        ("Cargo.toml", "[workspace]\nmembers = [\"foo\", \"bar\"]\n"),
+        (
+            "foo/Cargo.toml",
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+        ),
+        (
+            "bar/Cargo.toml",
+            "[package]\nname = \"bar\"\nversion = \"0.1.0\"\n",
+        ),
+    ])
+}
+
+fn version(metadata: &Metadata, name: &str) -> String {
+    metadata.version(name).unwrap().to_string()
+}
+
+#[test]
+fn undo_and_redo_each_call() {
+    let fixture = fixture();
+    let mut metadata = fixture.metadata();
+    assert!(!metadata.undo());
+
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.bump_patch_version("foo").unwrap();
+    assert_eq!(version(&metadata, "foo"), "0.1.2");
+
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.1");
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.0");
+    assert!(metadata.pending_changes().is_empty());
+    assert_eq!(metadata.modified_manifest_count(), 0);
+    assert!(!metadata.undo());
+
+    assert!(metadata.redo());
+    assert_eq!(version(&metadata, "foo"), "0.1.1");
+    assert_eq!(metadata.modified_manifest_count(), 1);
+
+    metadata.bump_minor_version("bar").unwrap();
+    assert!(!metadata.redo());
+}
+
+#[test]
+fn undo_reverts_whole_call() {
+    let fixture = fixture();
+    let mut metadata = fixture.metadata();
+    metadata.bump_all_patch_versions().unwrap();
+    metadata
+        .transaction(|metadata| {
+            metadata.bump_minor_version("foo").unwrap();
+            metadata.bump_minor_version("bar").unwrap();
+            Ok::<_, ManifestError>(())
+        })
+        .unwrap();
+
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.1");
+    assert_eq!(version(&metadata, "bar"), "0.1.1");
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.0");
+    assert_eq!(version(&metadata, "bar"), "0.1.0");
+}
+
+#[test]
+fn calls_without_edits_are_not_recorded() {
+    let fixture = fixture();
+    let mut metadata = fixture.metadata();
+    metadata.bump_patch_version("foo").unwrap();
+    assert!(metadata.bump_patch_version("missing").is_none());
+    let result: Result<(), _> = metadata.transaction(|metadata| {
+        metadata.bump_minor_version("bar").unwrap();
+        Err(ManifestError::PackageNotFound("missing".to_owned()))
+    });
+    assert!(result.is_err());
+
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.0");
+    assert!(!metadata.undo());
+}
+
+#[test]
+fn dump_clears_history() {
+    let fixture = fixture();
+    let mut metadata = fixture.metadata();
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.dump().unwrap();
+
+    assert!(!metadata.undo());
+}
+
+#[test]
+fn undo_and_redo_rename() {
+    let fixture = fixture();
+    let mut metadata = fixture.metadata();
+    metadata.rename_package("foo", "baz").unwrap();
+
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.0");
+    assert!(metadata.version("baz").is_none());
+    assert!(metadata.redo());
+    assert_eq!(version(&metadata, "baz"), "0.1.0");
+    assert!(metadata.version("foo").is_none());
+}
+
+#[test]
+fn failed_nested_transaction_keeps_outer_edits() {
+    let fixture = fixture();
+    let mut metadata = fixture.metadata();
+    metadata
+        .transaction(|metadata| {
+            metadata.bump_patch_version("foo").unwrap();
+            let inner: Result<(), _> = metadata.transaction(|metadata| {
+                metadata.bump_patch_version("foo").unwrap();
+                metadata.bump_patch_version("bar").unwrap();
+                Err(ManifestError::PackageNotFound("missing".to_owned()))
+            });
+            assert!(inner.is_err());
+            Ok::<_, ManifestError>(())
+        })
+        .unwrap();
+
+    assert_eq!(version(&metadata, "foo"), "0.1.1");
+    assert_eq!(version(&metadata, "bar"), "0.1.0");
+    assert_eq!(metadata.modified_manifest_count(), 1);
+    assert!(metadata.undo());
+    assert_eq!(version(&metadata, "foo"), "0.1.0");
+    assert_eq!(metadata.modified_manifest_count(), 0);
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use common::Fixture;
+
+#[test]
+fn clearing_default_members_keeps_a_plain_package() {
+    let fixture = Fixture::new(&[(
+        "Cargo.toml",
+        "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+    )]);
+    let mut metadata = fixture.metadata();
+    metadata.set_default_members(&[]).unwrap();
+    assert_eq!(metadata.modified_manifest_count(), 0);
+    metadata.bump_patch_version("foo").unwrap();
+    metadata.dump().unwrap();
+
+    assert_eq!(
+        fixture.read("Cargo.toml"),
+        "[package]\nname = \"foo\"\nversion = \"0.1.1\"\n"
+    );
+}